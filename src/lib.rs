@@ -22,21 +22,29 @@
 /// Miscellaneous utilities for use with the FLTK-rs GUI.
 /// 
 pub mod fltkutils {
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
+    use std::collections::{HashMap, VecDeque};
+    use std::fmt;
+    use std::fs;
     use std::mem::take;
+    use std::ops::Range;
+    use std::path::{Path, PathBuf};
     use std::rc::Rc;
-    use fltk::{app, button, button::Button, draw, group, menu, output, text};
+    use fltk::{app, browser, button, button::Button, dialog, draw, frame, group, image, input, menu, output, table, text, tree};
     use fltk::app::{quit, set_font_size, App};
     use fltk::draw::measure;
-    use fltk::enums::{Color, Font, Shortcut};
-    use fltk::prelude::{DisplayExt, GroupExt, InputExt, MenuExt, WidgetBase, WidgetExt, WindowExt};
+    use fltk::enums::{Align, CallbackTrigger, Color, ColorDepth, Event, Font, FrameType, Key, Shortcut};
+    use fltk::prelude::{BrowserExt, ButtonExt, DisplayExt, GroupExt, ImageExt, InputExt, MenuExt, TableExt, WidgetBase, WidgetExt, WindowExt};
     use fltk::text::{TextBuffer, TextEditor};
     use fltk::window::Window;
-    use lib_utils::vec::{vec_longest_str_len};
+    use regex::{NoExpand, Regex, RegexBuilder};
   
     /// Creates a simple, no-frills check box menu using FLTK's CheckButton struct.
     /// Returns a vector of the label strings of the boxes
     /// that were chosen by the user.
+    /// The window is sized to fit the labels, and typing in the Find field
+    /// narrows the list.  See `fltk_checkbox_menu_with()` for starting
+    /// checks, limits and grid layouts.
     /// Example:
     /// 
     ///     fn main() {
@@ -51,78 +59,433 @@ pub mod fltkutils {
     ///     app.run().unwrap();
     ///     }
     /// 
-    pub fn fltk_checkbox_menu(app: App, labels: &Vec<String>) -> Vec<String> {
+    pub fn fltk_checkbox_menu(app: App, labels: &[String]) -> Vec<String> {
+        fltk_checkbox_menu_with(&app, labels, &ChkboxMenuOptions::default())
+    }
 
-        // region Setup initial window & etc.
-        let mut win = Window::default().with_size(400, 300);
-        let mut submit = Button::new(160, 210, 80, 40, "Submit");
-        let pack = group::Pack::new(win.w()/2 - 100, 30, 100, 300, "");
-        // endregion
+    /// Settings for `fltk_checkbox_menu_with()`.
+    ///
+    #[derive(Debug, Clone)]
+    pub struct ChkboxMenuOptions {
+        /// The window title.
+        pub title: String,
+        /// Labels of the boxes that start out checked.
+        pub checked: Vec<String>,
+        /// The fewest boxes that must be checked before Submit can be clicked.
+        pub min: usize,
+        /// The most boxes that may be checked.  `None` means no limit.
+        pub max: Option<usize>,
+        /// How the boxes are arranged.
+        pub layout: ChkboxLayout,
+    }
+
+    /// How `fltk_checkbox_menu_with()` arranges its boxes.
+    ///
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum ChkboxLayout {
+        /// One box under another.
+        Column,
+        /// A grid with this many columns, filled a row at a time.
+        Columns(usize),
+        /// A grid with as many columns as fit across the screen.
+        FitWidth,
+    }
+
+    impl Default for ChkboxMenuOptions {
+        fn default() -> Self {
+            ChkboxMenuOptions {
+                title: String::new(),
+                checked: Vec::new(),
+                min: 0,
+                max: None,
+                layout: ChkboxLayout::Column,
+            }
+        }
+    }
+
+    impl ChkboxMenuOptions {
+        /// Sets the window title.
+        pub fn with_title(mut self, title: &str) -> Self {
+            self.title = title.to_string();
+            self
+        }
+
+        /// Sets the labels of the boxes that start out checked.
+        pub fn with_checked(mut self, checked: &[&str]) -> Self {
+            self.checked = checked.iter().map(|label| label.to_string()).collect();
+            self
+        }
+
+        /// Sets the fewest and the most boxes that may be checked.
+        pub fn with_limits(mut self, min: usize, max: Option<usize>) -> Self {
+            self.min = min;
+            self.max = max;
+            self
+        }
+
+        /// Sets how the boxes are arranged.
+        pub fn with_layout(mut self, layout: ChkboxLayout) -> Self {
+            self.layout = layout;
+            self
+        }
+
+        /// Returns the message to show when `count` boxes are checked,
+        /// or `None` if that count is allowed.
+        fn count_problem(&self, count: usize) -> Option<String> {
+            if count < self.min {
+                let plural = if self.min == 1 { "" } else { "es" };
+                return Some(format!("Check at least {} box{}.  ({} checked)", self.min, plural, count));
+            }
+            match self.max {
+                Some(max) if count > max => {
+                    let plural = if max == 1 { "" } else { "es" };
+                    Some(format!("Check no more than {} box{}.  ({} checked)", max, plural, count))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// Creates a check box menu set up from `opts`.  Some boxes can start
+    /// out checked, there are "Select all" and "Select none" buttons, and
+    /// Submit stays greyed out -- with a note saying why -- until the number
+    /// of checked boxes is between `opts.min` and `opts.max`.
+    /// The window is sized to fit the labels, within the screen; labels that
+    /// still don't fit are cut short, with the full text in a tooltip.
+    /// The list scrolls if it is too long for the window, and typing in
+    /// the Find field narrows it.  "Select all" and "Select none" only
+    /// touch the boxes that are showing.
+    /// `opts.layout` can put the boxes in a grid of several columns, which
+    /// suits long lists of short labels; Tab follows reading order.
+    /// Returns the labels of the checked boxes in the order they were
+    /// passed in, or an empty vector if the window is closed.
+    ///
+    /// Example:
+    ///
+    ///     use lib_myfltk::fltkutils::*;
+    ///
+    ///     let app = fltk::app::App::default();
+    ///
+    ///     let labels = vec!["flamingo".to_string(), "tiger".to_string(), "lion".to_string()];
+    ///     let opts = ChkboxMenuOptions::default()
+    ///         .with_title("Pick two animals")
+    ///         .with_checked(&["tiger"])
+    ///         .with_limits(2, Some(2));
+    ///
+    ///     let choice = fltk_checkbox_menu_with(&app, &labels, &opts);
+    ///
+    ///     println!("\n choice: {:?} \n", choice);
+    ///
+    pub fn fltk_checkbox_menu_with(app: &App, labels: &[String], opts: &ChkboxMenuOptions) -> Vec<String> {
+        checkbox_menu_indices(app, labels, opts).into_iter()
+            .map(|idx| labels[idx].clone())
+            .collect()
+    }
+
+    /// The sizes of a selection menu's list and the labels to show in it.
+    struct MenuLayout {
+        list_w: i32,
+        list_h: i32,
+        cols: usize,
+        col_w: i32,
+        shown: Vec<String>,      // The labels, with any that are too wide shortened.
+    }
+
+    impl MenuLayout {
+        const ROW_H: i32 = 30;
+        const EDGE_W: i32 = 30;  // The list's indent & scrollbar.
+
+        /// Fits a list of `labels` on the screen in one column.  The list
+        /// is made wide enough for the longest label, as measured in the
+        /// current font, and tall enough for every row, but the window --
+        /// which needs `extra_w` by `extra_h` around the list -- stays on
+        /// the screen.  `mark_w` is the room each row needs besides its
+        /// label, and the list is never narrower than `min_w`.  Labels that
+        /// still don't fit are cut short with "...".
+        fn fit(labels: &[String], mark_w: i32, min_w: i32, extra_w: i32, extra_h: i32) -> MenuLayout {
+            MenuLayout::fit_grid(labels, mark_w, Some(1), min_w, extra_w, extra_h)
+        }
+
+        /// Same as `fit()`, but lays the labels out in `cols` columns, or
+        /// in as many as fit across the screen if `cols` is `None`.
+        fn fit_grid(labels: &[String], mark_w: i32, cols: Option<usize>,
+                    min_w: i32, extra_w: i32, extra_h: i32) -> MenuLayout {
+            let (screen_w, screen_h) = app::screen_size();
+            let max_w = (screen_w as i32 - 80 - extra_w).max(min_w);
+            let max_h = (screen_h as i32 - 120 - extra_h).max(90);
+
+            // Like fltk_size_bttn_to_fit_label(), measuring only works while a window is shown.
+            let mut measurewin = Window::default().with_size(40, 20);
+            measurewin.show();
+            draw::set_font(Font::Helvetica, app::font_size());
+
+            let widest = labels.iter().map(|label| measure(label, false).0).max().unwrap_or(0);
+            let cell_w = widest + mark_w - MenuLayout::EDGE_W;
+            let room_w = max_w - MenuLayout::EDGE_W;
+            let cols = cols.unwrap_or((room_w / cell_w.max(1)) as usize)
+                .clamp(1, labels.len().max(1));
+            let col_w = cell_w.min(room_w / cols as i32).max(60);
+            let list_w = (col_w * cols as i32 + MenuLayout::EDGE_W).max(min_w);
+            let room = col_w - (mark_w - MenuLayout::EDGE_W);
+            let shown = labels.iter()
+                .map(|label| MenuLayout::ellipsize(label, room))
+                .collect();
+
+            measurewin.hide();
+
+            let rows = labels.len().div_ceil(cols);
+            let list_h = (rows as i32 * MenuLayout::ROW_H + 10).clamp(90, max_h);
+            MenuLayout { list_w, list_h, cols, col_w, shown }
+        }
+
+        /// Puts the showing buttons in a grid of `cols` columns starting at
+        /// `x`, `y`, in reading order, so Tab moves along each row in turn.
+        /// Hidden buttons are parked at the start so they don't stretch the scroll area.
+        fn place<B: WidgetExt>(buttons: &mut [B], x: i32, y: i32, cols: usize, col_w: i32) {
+            let mut cell = 0;
+            for button in buttons.iter_mut() {
+                if button.visible() {
+                    let (row, col) = ((cell / cols) as i32, (cell % cols) as i32);
+                    button.resize(x + col * col_w, y + row * MenuLayout::ROW_H, col_w, MenuLayout::ROW_H);
+                    cell += 1;
+                } else {
+                    button.resize(x, y, col_w, MenuLayout::ROW_H);
+                }
+            }
+        }
+
+        /// Cuts `label` short with "..." so it fits in `room` pixels.
+        /// The font must already be set.
+        fn ellipsize(label: &str, room: i32) -> String {
+            if measure(label, false).0 <= room {
+                return label.to_string();
+            }
+            let mut chars: Vec<char> = label.chars().collect();
+            while !chars.is_empty() {
+                chars.pop();
+                let short = format!("{}...", chars.iter().collect::<String>().trim_end());
+                if measure(&short, false).0 <= room {
+                    return short;
+                }
+            }
+            "...".to_string()
+        }
+    }
+
+    /// Runs the check box menu for `fltk_checkbox_menu_with()` and
+    /// returns the indices of the checked boxes.
+    fn checkbox_menu_indices(app: &App, labels: &[String], opts: &ChkboxMenuOptions) -> Vec<usize> {
 
-        // region Create the checkboxes within the pack..
+        // region Set up the window & the scrolling list of checkboxes.
+        // Each row needs room for the box, the list's indent & the scrollbar.
+        let layout = match opts.layout {
+            ChkboxLayout::Column => MenuLayout::fit(labels, 70, 380, 20, 170),
+            ChkboxLayout::Columns(cols) => MenuLayout::fit_grid(labels, 70, Some(cols), 380, 20, 170),
+            ChkboxLayout::FitWidth => MenuLayout::fit_grid(labels, 70, None, 380, 20, 170),
+        };
+        let (list_w, list_h) = (layout.list_w, layout.list_h);
+        let (cols, col_w) = (layout.cols, layout.col_w);
+        let mut win = Window::default().with_size(list_w + 20, list_h + 170).with_label(&opts.title);
+
+        // The boxes are made in reading order, which is also the Tab order.
+        let scroll = group::Scroll::new(10, 50, list_w, list_h, None);
         let mut chkbttns = Vec::new();
-        for item in labels {
-            let _checkbox = button::CheckButton::default()
-                .with_label(item)
-                .with_size(0, 30);  // The pack takes care of positioning the checkboxes?
-            chkbttns.push(_checkbox);
+        for (label, shown) in labels.iter().zip(&layout.shown) {
+            let mut chkbttn = button::CheckButton::default().with_label(shown);
+            if shown != label {
+                chkbttn.set_tooltip(label);
+            }
+            chkbttn.set_checked(opts.checked.contains(label));
+            chkbttns.push(chkbttn);
         }
+        scroll.end();
+        MenuLayout::place(&mut chkbttns, 20, 55, cols, col_w);
         // endregion
 
-        // region End the pack & window.
-        pack.end();
-        win.end();
-        win.show();
+        // region Set up the buttons & the note under the list.
+        let mut selectall = Button::new(10, list_h + 60, 120, 30, "Select all");
+        let mut selectnone = Button::new(140, list_h + 60, 120, 30, "Select none");
+
+        let mut note = frame::Frame::new(10, list_h + 95, list_w, 20, None);
+        note.set_label_color(Color::Red);
+        note.set_label_size(12);
+
+        let mut submit = Button::new(list_w / 2 - 30, list_h + 125, 80, 35, "Submit");
         // endregion
 
-        // region Do the Submit button callback.
+        // region Set up the Find field.  Hidden boxes keep their checks.
+        let mut chkbttns_fltr = chkbttns.clone();
+        let labels_fltr = labels.to_vec();
+        let mut scroll_fltr = scroll.clone();
+        let mut filter = add_filter_row(10, 10, list_w, move |query, mode| {
+            filter_buttons(&mut chkbttns_fltr, &labels_fltr, query, mode, &mut scroll_fltr);
+            MenuLayout::place(&mut chkbttns_fltr, 20, 55, cols, col_w);
+        });
 
-        // Create the Rc to extract the value.  Then clone it for the callback.
-        let newvec: Vec<String> = Vec::new();
-        let checked = Rc::new(RefCell::new(newvec));
-        let checked_clone = checked.clone();
-        let chkbttns_clone = chkbttns.clone();
+        win.end();
+        win.show();
+        let _ = filter.take_focus();
+        // endregion
 
-        submit.set_callback(move |_| {
-            for item in &chkbttns_clone {
-                if item.is_checked() {
-                    checked_clone.borrow_mut().push(item.label().to_string());
+        // region Grey out Submit whenever the number of checked boxes breaks the limits.
+        let opts_cnt = opts.clone();
+        let chkbttns_cnt = chkbttns.clone();
+        let note_cnt = note.clone();
+        let submit_cnt = submit.clone();
+        let update_count = Rc::new(move || {
+            let (mut note_cnt, mut submit_cnt) = (note_cnt.clone(), submit_cnt.clone());
+            let count = chkbttns_cnt.iter().filter(|chk| chk.is_checked()).count();
+            match opts_cnt.count_problem(count) {
+                Some(msg) => {
+                    note_cnt.set_label(&msg);
+                    submit_cnt.deactivate();
+                }
+                None => {
+                    note_cnt.set_label("");
+                    submit_cnt.activate();
                 }
             }
+        });
+        update_count();
+
+        for chkbttn in chkbttns.iter_mut() {
+            let update_count = update_count.clone();
+            chkbttn.set_callback(move |_| update_count());
+        }
 
-            println!("\n The clone within the callback is: {:?} \n", *checked_clone.borrow_mut());
-            win.hide();  // close the window
+        let mut chkbttns_all = chkbttns.clone();
+        let update_all = update_count.clone();
+        selectall.set_callback(move |_| {
+            chkbttns_all.iter_mut().filter(|chk| chk.visible()).for_each(|chk| chk.set_checked(true));
+            update_all();
+        });
 
+        let mut chkbttns_none = chkbttns.clone();
+        let update_none = update_count.clone();
+        selectnone.set_callback(move |_| {
+            chkbttns_none.iter_mut().filter(|chk| chk.visible()).for_each(|chk| chk.set_checked(false));
+            update_none();
         });
+        // endregion
 
-        // Run the app
-        while app.wait(){}
+        // region Do the Submit button callback.
+        let checked: Rc<RefCell<Vec<usize>>> = Rc::new(RefCell::new(Vec::new()));
+        let checked_clone = checked.clone();
+        let chkbttns_clone = chkbttns.clone();
+        let mut win_clone = win.clone();
+        submit.set_callback(move |_| {
+            *checked_clone.borrow_mut() = chkbttns_clone.iter().enumerate()
+                .filter(|(_, chk)| chk.is_checked())
+                .map(|(idx, _)| idx)
+                .collect();
+            win_clone.hide();
+        });
+        // endregion
 
-        println!("\n The checked vector after exiting the callback is: {:?} \n", *checked.borrow_mut());
-        let returnvec = take(&mut *checked.borrow_mut());
-        returnvec
+        while win.shown() {
+            if !app.wait() {
+                break;
+            }
+        }
 
+        let retvec = take(&mut *checked.borrow_mut());
+        retvec
     }
 
     /// Creates a checkbox shift menu of the items passed to the function
     /// in the `flist` vector.  Returns a vector of the items that were
-    /// chosen by the user.
-    pub fn fltk_chkbox_shift_menu(flist: &Vec<String>) -> Vec<String> {
-        // todo: This uses RefCell and Rc.  Is there a better way to do it?
+    /// chosen by the user.  Each click of `@>` replaces the chosen items
+    /// with the ones checked at the time.  For a dialog where items can
+    /// also be moved back and reordered, see `fltk_transfer_menu()`.
+    pub fn fltk_chkbox_shift_menu(flist: &[String]) -> Vec<String> {
+        fltk_chkbox_shift_menu_by(flist, |item| item.clone()).into_iter()
+            .map(|idx| flist[idx].clone())
+            .collect()
+    }
+
+    /// Creates a menu of radio buttons using the `items` vector.
+    /// Active items are highlighted by a small light.
+    /// The window is sized to fit the items, and typing in the Find field
+    /// narrows the list.  Returns the chosen item, or
+    /// an empty string if nothing was chosen; `fltk_radio_menu_with()`
+    /// returns an `Option` instead.
+    pub fn fltk_radio_lightbtn_menu(items: &[String], prompt: &str) -> String {
+        radio_menu_index(items, &RadioMenuOptions::default().with_prompt(prompt))
+            .map(|idx| items[idx].clone())
+            .unwrap_or_default()
+    }
 
-        let newvec: RefCell<Vec<String>> = RefCell::new(Vec::new());
-        let keepers: Rc<RefCell<Vec<String>>> = Rc::new(newvec);
+    /// Same as `fltk_checkbox_menu_with()`, but works on any slice of items
+    /// and returns the indices of the checked items in input order, so
+    /// items with the same label can be told apart.  The `label` closure
+    /// gives the text for each item's box.
+    ///
+    /// Example:
+    ///
+    ///     use lib_myfltk::fltkutils::*;
+    ///
+    ///     struct Question { id: u32, text: String }
+    ///
+    ///     let app = fltk::app::App::default();
+    ///
+    ///     let bank = vec![
+    ///         Question { id: 7, text: "Solve for x".to_string() },
+    ///         Question { id: 9, text: "Solve for x".to_string() },
+    ///     ];
+    ///
+    ///     let picked = fltk_checkbox_menu_by(&app, &bank, |q| q.text.clone(),
+    ///                                        &ChkboxMenuOptions::default());
+    ///     let ids: Vec<u32> = picked.iter().map(|&idx| bank[idx].id).collect();
+    ///
+    ///     println!("\n picked ids: {:?} \n", ids);
+    ///
+    pub fn fltk_checkbox_menu_by<T, F>(app: &App, items: &[T], label: F, opts: &ChkboxMenuOptions) -> Vec<usize>
+        where F: Fn(&T) -> String
+    {
+        let labels: Vec<String> = items.iter().map(label).collect();
+        checkbox_menu_indices(app, &labels, opts)
+    }
+
+    /// Same as `fltk_checkbox_menu_by()`, but returns references to the
+    /// checked items instead of their indices.
+    pub fn fltk_checkbox_menu_items<'a, T, F>(app: &App, items: &'a [T], label: F,
+                                             opts: &ChkboxMenuOptions) -> Vec<&'a T>
+        where F: Fn(&T) -> String
+    {
+        fltk_checkbox_menu_by(app, items, label, opts).into_iter()
+            .map(|idx| &items[idx])
+            .collect()
+    }
 
-        let mut win = Window::default().with_size(400, 300);
+    /// Same as `fltk_chkbox_shift_menu()`, but works on any slice of items
+    /// and returns the indices of the items that were shifted across, in
+    /// input order.  The `label` closure gives the text for each item.
+    pub fn fltk_chkbox_shift_menu_by<T, F>(items: &[T], label: F) -> Vec<usize>
+        where F: Fn(&T) -> String
+    {
+        let labels: Vec<String> = items.iter().map(label).collect();
+
+        let keepers: Rc<RefCell<Vec<usize>>> = Rc::new(RefCell::new(Vec::new()));
+
+        // The check boxes get a column sized to fit the labels; the list of
+        // shifted items gets another 200 pixels beside the @> button.
+        let layout = MenuLayout::fit(&labels, 70, 150, 250, 20);
+        let mut win = Window::default().with_size(layout.list_w + 250, layout.list_h + 20);
         let mut row = group::Flex::default_fill().row();
+        row.set_margin(10);
         let scroll = group::Scroll::default();
-        row.fixed(&scroll, 150);
-        let pack = group::Pack::default().with_size(100, 300);
+        row.fixed(&scroll, layout.list_w);
+        let pack = group::Pack::default().with_size(layout.list_w - MenuLayout::EDGE_W, labels.len() as i32 * MenuLayout::ROW_H);
 
-        for file in flist {
-            let _check = button::CheckButton::default()
-                .with_label(file)
-                .with_size(0, 30);
+        let mut chkbttns = Vec::new();
+        for (item, shown) in labels.iter().zip(&layout.shown) {
+            let mut chkbttn = button::CheckButton::default()
+                .with_label(shown)
+                .with_size(0, MenuLayout::ROW_H);
+            if shown != item {
+                chkbttn.set_tooltip(item);
+            }
+            chkbttns.push(chkbttn);
         }
 
         pack.end();
@@ -136,616 +499,6072 @@ pub mod fltkutils {
         win.end();
         win.show();
 
+        // Each click replaces the shifted items with the ones checked now.
         let keepers_clone = Rc::clone(&keepers);
         btn.set_callback(move |_b| {
-            output.set_value("");
-            let mut string = String::new();
-            for i in 0..pack.children() {
-                let check: button::CheckButton = button::CheckButton::
-                from_dyn_widget(&pack.child(i).unwrap()).unwrap();
-                if check.is_checked() {
-                    string.push_str(&check.label());
-                    string.push('\n');
-                    keepers_clone.borrow_mut().push(check.label().clone());
-                }
-            }
-            output.set_value(&string);
+            let shifted: Vec<usize> = chkbttns.iter().enumerate()
+                .filter(|(_, chk)| chk.is_checked())
+                .map(|(idx, _)| idx)
+                .collect();
+            let text: Vec<&str> = shifted.iter().map(|&idx| labels[idx].as_str()).collect();
+            output.set_value(&text.join("\n"));
+            *keepers_clone.borrow_mut() = shifted;
         });
 
         while win.shown() {
             app::wait();
         }
 
-        let retvec: Vec<String> = take(&mut keepers.borrow_mut());
+        let retvec: Vec<usize> = take(&mut keepers.borrow_mut());
         retvec
     }
 
-    /// Creates a menu of radio buttons using the `items` vector.
-    /// Active items are highlighted by a small light.
-    pub fn fltk_radio_lightbtn_menu(items: &Vec<String>, prompt: &str) -> String {
+    /// The two panes of `fltk_transfer_menu()` and the indices in the right one.
+    struct TransferPanes {
+        labels: Vec<String>,
+        chosen: Vec<usize>,
+        left: browser::MultiBrowser,
+        right: browser::MultiBrowser,
+    }
 
-        // region Set up the variables.
-        // todo: This uses RefCell and Rc.  Is there a better way to do it.
-        let newstring: RefCell<String> = RefCell::new("".to_string());
-        let keepers: Rc<RefCell<String>> = Rc::new(newstring);
-        let longlen = vec_longest_str_len(items);
-        // endregion
+    impl TransferPanes {
+        /// Returns the indices of the items in the left pane, in pane order.
+        fn available(&self) -> Vec<usize> {
+            (0..self.labels.len()).filter(|idx| !self.chosen.contains(idx)).collect()
+        }
 
-        // region Set up the window & group widgets.
-        let mut win = Window::default().with_size(400, 300).with_label(prompt);
-        let flex = group::Flex::default().with_size(250, 300);   // Do you really need this?
-        let scroll = group::Scroll::default().with_size(200, longlen as i32 + 10);
-        let pack = group::Pack::default().with_size(200, longlen as i32 + 10);  // Need this to organize the buttons.
-        // endregion
+        /// Returns the selected rows of `pane`, counting from 0.
+        fn selected_rows(pane: &browser::MultiBrowser) -> Vec<usize> {
+            (1..=pane.size()).filter(|&line| pane.selected(line)).map(|line| line as usize - 1).collect()
+        }
 
-        // region Create the radio buttons.
-        for element in items {
-            let _radio = button::RadioLightButton::default()
-                .with_label(element)
-                .with_size(longlen as i32, 30);
+        /// Redraws both panes from `chosen`, then selects `reselect` rows on the right.
+        fn refresh(&mut self, reselect: &[usize]) {
+            self.left.clear();
+            for idx in self.available() {
+                self.left.add(&format!("@.{}", self.labels[idx]));  // "@." stops FLTK from reading '@' as a format code.
+            }
+            self.right.clear();
+            for &idx in &self.chosen {
+                self.right.add(&format!("@.{}", self.labels[idx]));
+            }
+            for &row in reselect {
+                self.right.select(row as i32 + 1);
+            }
         }
-        // endregion
 
-        // region End adding widgets to the groups & create the submit button.
-        pack.end();
-        scroll.end();
-        flex.end();
+        fn add_selected(&mut self) {
+            let available = self.available();
+            for row in TransferPanes::selected_rows(&self.left) {
+                self.chosen.push(available[row]);
+            }
+            self.refresh(&[]);
+        }
+
+        fn add_all(&mut self) {
+            let available = self.available();
+            self.chosen.extend(available);
+            self.refresh(&[]);
+        }
+
+        fn remove_selected(&mut self) {
+            let rows = TransferPanes::selected_rows(&self.right);
+            let mut row = 0;
+            self.chosen.retain(|_| {
+                row += 1;
+                !rows.contains(&(row - 1))
+            });
+            self.refresh(&[]);
+        }
+
+        fn remove_all(&mut self) {
+            self.chosen.clear();
+            self.refresh(&[]);
+        }
+
+        /// Moves the selected rows of the right pane up or down by one.
+        /// A row stops when it reaches the end or another selected row.
+        fn move_selected(&mut self, up: bool) {
+            let mut rows = TransferPanes::selected_rows(&self.right);
+            if up {
+                for i in 0..rows.len() {
+                    let row = rows[i];
+                    if row > 0 && !rows.contains(&(row - 1)) {
+                        self.chosen.swap(row - 1, row);
+                        rows[i] = row - 1;
+                    }
+                }
+            } else {
+                for i in (0..rows.len()).rev() {
+                    let row = rows[i];
+                    if row + 1 < self.chosen.len() && !rows.contains(&(row + 1)) {
+                        self.chosen.swap(row, row + 1);
+                        rows[i] = row + 1;
+                    }
+                }
+            }
+            self.refresh(&rows);
+        }
+    }
 
-        let mut submit = Button::new(300, 175, 75, 30, "Submit");
+    /// Creates a two-pane transfer dialog.  The left pane holds the items
+    /// that are still available and the right pane the ones chosen so far.
+    /// `>` and `<` move the selected items across, `>>` and `<<` move all
+    /// of them, and double-clicking an item moves it to the other side.
+    /// Up and Down reorder the selected items in the right pane.  An item
+    /// can only be in one pane, so the result never holds duplicates.
+    /// `chosen` lists the indices that start out in the right pane.
+    /// Returns the indices in the right pane, in their order, when OK is
+    /// clicked, or `None` if Cancel is clicked or the window is closed.
+    ///
+    /// Example:
+    ///
+    ///     use lib_myfltk::fltkutils::*;
+    ///
+    ///     let _app = fltk::app::App::default();
+    ///
+    ///     let labels: Vec<String> = ["flamingo", "tiger", "lion", "otter"]
+    ///         .iter().map(|s| s.to_string()).collect();
+    ///
+    ///     if let Some(chosen) = fltk_transfer_menu(&labels, &[2], "Pick the animals") {
+    ///         println!("\n chosen: {:?} \n", chosen);
+    ///     }
+    ///
+    pub fn fltk_transfer_menu(labels: &[String], chosen: &[usize], title: &str) -> Option<Vec<usize>> {
+
+        // region Set up the window, the two panes & the buttons.
+        let mut win = Window::default().with_size(560, 430).with_label(title);
+
+        let mut left = browser::MultiBrowser::new(10, 30, 220, 340, "Available");
+        left.set_align(Align::TopLeft);
+        let mut right = browser::MultiBrowser::new(330, 30, 220, 340, "Chosen");
+        right.set_align(Align::TopLeft);
+
+        let mut addsome = Button::new(250, 60, 60, 30, "@>");
+        addsome.set_tooltip("Move the selected items to the right");
+        let mut addall = Button::new(250, 100, 60, 30, "@>>");
+        addall.set_tooltip("Move every item to the right");
+        let mut removesome = Button::new(250, 150, 60, 30, "@<");
+        removesome.set_tooltip("Move the selected items back to the left");
+        let mut removeall = Button::new(250, 190, 60, 30, "@<<");
+        removeall.set_tooltip("Move every item back to the left");
+        let mut moveup = Button::new(250, 260, 60, 30, "Up");
+        let mut movedown = Button::new(250, 300, 60, 30, "Down");
+
+        let mut ok = button::ReturnButton::new(360, 385, 90, 35, "OK");
+        let mut cancel = Button::new(460, 385, 90, 35, "Cancel");
 
         win.end();
         win.show();
         // endregion
 
-        // region Use the button callbacks to get the selected radio button.
-        let keepers_clone = Rc::clone(&keepers);
-        let mut win_clone = win.clone();
-        submit.set_callback(move |_b| {
-            for i in 0..pack.children() {
-                let radio: button::RadioLightButton = 
-                    button::RadioLightButton::from_dyn_widget(&pack.child(i)
-                        .unwrap()).unwrap();  // Complicated.  Is there a better way?
-                if radio.is_toggled() {
-                    *keepers_clone.borrow_mut() = radio.label().clone();
-                }
+        // region Fill the panes.  Bad or repeated starting indices are dropped.
+        let mut start: Vec<usize> = Vec::new();
+        for &idx in chosen {
+            if idx < labels.len() && !start.contains(&idx) {
+                start.push(idx);
             }
-            win_clone.hide();
-        });
-
-        while win.shown() {
-            app::wait();
         }
+        let panes = Rc::new(RefCell::new(TransferPanes {
+            labels: labels.to_vec(),
+            chosen: start,
+            left: left.clone(),
+            right: right.clone(),
+        }));
+        panes.borrow_mut().refresh(&[]);
         // endregion
 
-        let ret: String = keepers.borrow().clone();
-        ret
-    }
+        // region Do the pane & arrow button callbacks.
+        let panes_dbl = panes.clone();
+        left.set_callback(move |_| {
+            if app::event_clicks() {
+                panes_dbl.borrow_mut().add_selected();
+            }
+        });
+        let panes_dbl = panes.clone();
+        right.set_callback(move |_| {
+            if app::event_clicks() {
+                panes_dbl.borrow_mut().remove_selected();
+            }
+        });
 
-    /// Creates a simple, no-frills editor using FLTK's TextEditor struct.
-    /// Returns the final contents of the editor.
-    pub fn fltk_simple_editor(startertxt: &str, winlabel: &str) -> String {
-        let edtr = App::default();
-        let mut buf = TextBuffer::default();
-        let mut win = Window::default().with_size(800, 300);
-        set_font_size(20);
-        win.set_color(Color::Yellow);
-        win.set_label(winlabel);
-        win.make_resizable(true);
+        let panes_btn = panes.clone();
+        addsome.set_callback(move |_| panes_btn.borrow_mut().add_selected());
+        let panes_btn = panes.clone();
+        addall.set_callback(move |_| panes_btn.borrow_mut().add_all());
+        let panes_btn = panes.clone();
+        removesome.set_callback(move |_| panes_btn.borrow_mut().remove_selected());
+        let panes_btn = panes.clone();
+        removeall.set_callback(move |_| panes_btn.borrow_mut().remove_all());
+        let panes_btn = panes.clone();
+        moveup.set_callback(move |_| panes_btn.borrow_mut().move_selected(true));
+        let panes_btn = panes.clone();
+        movedown.set_callback(move |_| panes_btn.borrow_mut().move_selected(false));
+        // endregion
 
-        fltk_simple_editor_menubar();
+        // region Do the OK & Cancel callbacks.
+        let result: Rc<RefCell<Option<Vec<usize>>>> = Rc::new(RefCell::new(None));
+        let result_ok = result.clone();
+        let panes_ok = panes.clone();
+        let mut win_ok = win.clone();
+        ok.set_callback(move |_| {
+            *result_ok.borrow_mut() = Some(panes_ok.borrow().chosen.clone());
+            win_ok.hide();
+        });
 
-        buf.set_text(startertxt);
-        let mut simped = TextEditor::default()
-            .with_size(770, 222)
-            .center_of_parent();
+        let mut win_cancel = win.clone();
+        cancel.set_callback(move |_| win_cancel.hide());
+        // endregion
 
-        simped.set_buffer(buf.clone());   // Clone is used here to avoid an ownership error.
-        simped.wrap_mode(text::WrapMode::AtBounds, 0);
-        simped.set_color(Color::White);
-        simped.set_text_size(22);
-        simped.set_text_color(Color::Black);
+        while win.shown() {
+            app::wait();
+        }
 
-        win.end();
-        win.show();
+        let ret = result.borrow_mut().take();
+        ret
+    }
 
-        edtr.run().unwrap();
+    /// Same as `fltk_transfer_menu()`, but works on any slice of items.
+    /// The `label` closure gives the text for each item.
+    pub fn fltk_transfer_menu_by<T, F>(items: &[T], label: F, chosen: &[usize], title: &str) -> Option<Vec<usize>>
+        where F: Fn(&T) -> String
+    {
+        let labels: Vec<String> = items.iter().map(label).collect();
+        fltk_transfer_menu(&labels, chosen, title)
+    }
 
-        buf.text()
+    /// The labels and current order behind a `RankList`.
+    struct RankState {
+        labels: Vec<String>,
+        order: Vec<usize>,          // order[rank] is the index of the item at that rank.
+        current: usize,
+        drag_from: Option<usize>,
+        drop_at: Option<usize>,
     }
 
-    /// Creates a menubar to be used with the `fltk_simple_editor()`.
-    /// The new menubar only has ine entry -- `File/Quit` -- that provides
-    /// a pattern that can be used to create more menu items
-    pub fn fltk_simple_editor_menubar() -> menu::MenuBar {
+    impl RankState {
+        /// Moves the item at rank `from` to rank `to`, shifting the ones between.
+        fn move_item(&mut self, from: usize, to: usize) {
+            let item = self.order.remove(from);
+            self.order.insert(to, item);
+            self.current = to;
+        }
+    }
 
-        let mut menubar = menu::MenuBar::new(0, 0, 800, 40, "");
+    /// A list whose items can be put in order by dragging them with the
+    /// mouse, or by pressing Alt+Up and Alt+Down to move the current item.
+    /// Up, Down, Page Up, Page Down, Home and End change the current item.  The list's
+    /// callback runs whenever an item is moved.
+    ///
+    /// Example:
+    ///
+    ///     use fltk::{prelude::*, *};
+    ///     use lib_myfltk::fltkutils::RankList;
+    ///
+    ///     let app = app::App::default();
+    ///     let mut win = window::Window::default().with_size(300, 300);
+    ///
+    ///     let steps = vec!["Rinse".to_string(), "Wash".to_string(), "Dry".to_string()];
+    ///     let list = RankList::new(10, 10, 280, 280, steps);
+    ///
+    ///     win.end();
+    ///     win.show();
+    ///     app.run().unwrap();
+    ///
+    ///     println!("\n order: {:?} \n", list.order());
+    ///
+    #[derive(Clone)]
+    pub struct RankList {
+        inner: table::Table,
+        state: Rc<RefCell<RankState>>,
+    }
 
-        let quit_idx = menubar.add(
-            "File/Finished\t",
-            Shortcut::None,
-            menu::MenuFlag::Normal,
-            |_| {
-                quit();
-            },
-        );
-        menubar.at(quit_idx).unwrap().set_label_color(Color::Red);
+    fltk::widget_extends!(RankList, table::Table, inner);
+
+    impl RankList {
+        const ROW_H: i32 = 28;
+
+        /// Creates the list with the items in the order given.
+        pub fn new(x: i32, y: i32, w: i32, h: i32, labels: Vec<String>) -> Self {
+            let count = labels.len();
+            let state = Rc::new(RefCell::new(RankState {
+                labels,
+                order: (0..count).collect(),
+                current: 0,
+                drag_from: None,
+                drop_at: None,
+            }));
+
+            // region Set up the table -- one column, one row per item.
+            let mut inner = table::Table::new(x, y, w, h, None);
+            inner.set_rows(count as i32);
+            inner.set_cols(1);
+            inner.set_row_header(false);
+            inner.set_col_header(false);
+            inner.set_row_height_all(RankList::ROW_H);
+            inner.set_col_width_all(w - 20);  // Leave room for the scrollbar.
+            inner.end();
+            // endregion
+
+            // region Draw each row as its rank and label, with a line where a dragged item will land.
+            let state_draw = state.clone();
+            inner.draw_cell(move |t, ctx, row, _col, x, y, w, h| {
+                if ctx != table::TableContext::Cell {
+                    return;
+                }
+                let st = state_draw.borrow();
+                let rank = row as usize;
+                let Some(&idx) = st.order.get(rank) else { return };
+
+                draw::push_clip(x, y, w, h);
+                let bg = if rank == st.current { Color::from_rgb(220, 230, 245) } else { Color::Background2 };
+                draw::draw_box(FrameType::FlatBox, x, y, w, h, bg);
+                draw::set_draw_color(Color::Foreground);
+                draw::set_font(Font::Helvetica, app::font_size());
+                draw::draw_text2(&format!("{}.", rank + 1), x + 4, y, 36, h, Align::Right);
+                draw::draw_text2(&st.labels[idx], x + 48, y, w - 52, h, Align::Left);
+                draw::set_draw_color(Color::Light2);
+                draw::draw_line(x, y + h - 1, x + w, y + h - 1);
+
+                if let (Some(from), Some(to)) = (st.drag_from, st.drop_at) {
+                    if to == rank && to != from {
+                        let line_y = if to < from { y + 1 } else { y + h - 2 };
+                        draw::set_draw_color(Color::Selection);
+                        draw::set_line_style(draw::LineStyle::Solid, 3);
+                        draw::draw_line(x + 2, line_y, x + w - 2, line_y);
+                        draw::set_line_style(draw::LineStyle::Solid, 0);
+                    }
+                }
+                if rank == st.current && t.has_focus() {
+                    draw::set_draw_color(Color::Foreground);
+                    draw::set_line_style(draw::LineStyle::Dot, 1);
+                    draw::draw_rect(x + 1, y + 1, w - 2, h - 2);
+                    draw::set_line_style(draw::LineStyle::Solid, 0);
+                }
+                draw::pop_clip();
+            });
+            // endregion
+
+            // region Drag items with the mouse & move them from the keyboard.
+            let state_hndl = state.clone();
+            inner.handle(move |t, ev| match ev {
+                Event::Focus | Event::Unfocus => {
+                    t.redraw();
+                    true
+                }
+                Event::Push => {
+                    // Clicks outside the cells, like on the scrollbar, are left to the table.
+                    match t.cursor2rowcol() {
+                        Some((table::TableContext::Cell, row, _, _)) => {
+                            let mut st = state_hndl.borrow_mut();
+                            st.current = row as usize;
+                            st.drag_from = Some(row as usize);
+                            st.drop_at = Some(row as usize);
+                            drop(st);
+                            let _ = t.take_focus();
+                            t.redraw();
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+                Event::Drag => {
+                    if state_hndl.borrow().drag_from.is_none() {
+                        return false;
+                    }
+                    // Scroll when the mouse is dragged past the top or bottom.
+                    let top = t.row_position();
+                    if app::event_y() < t.y() {
+                        t.set_row_position((top - 1).max(0));
+                    } else if app::event_y() > t.y() + t.h() {
+                        t.set_row_position(top + 1);
+                    }
+                    if let Some((table::TableContext::Cell, row, _, _)) = t.cursor2rowcol() {
+                        state_hndl.borrow_mut().drop_at = Some(row as usize);
+                    }
+                    t.redraw();
+                    true
+                }
+                Event::Released => {
+                    let mut st = state_hndl.borrow_mut();
+                    let moved = match (st.drag_from.take(), st.drop_at.take()) {
+                        (Some(from), Some(to)) if from != to => {
+                            st.move_item(from, to);
+                            true
+                        }
+                        (Some(_), _) => false,
+                        _ => return false,
+                    };
+                    drop(st);
+                    t.redraw();
+                    if moved {
+                        t.do_callback();
+                    }
+                    true
+                }
+                Event::KeyDown => {
+                    let mut st = state_hndl.borrow_mut();
+                    let count = st.order.len();
+                    if count == 0 {
+                        return false;
+                    }
+                    let current = st.current.min(count - 1);
+                    let alt = app::is_event_alt();
+                    let key = app::event_key();
+                    if alt && key != Key::Up && key != Key::Down {
+                        return false;
+                    }
+                    let page = rows_per_page(t, RankList::ROW_H);
+                    let Some(next) = row_for_key(key, current, count, page) else { return false };
+                    let moved = alt && next != current;
+                    if moved {
+                        st.move_item(current, next);
+                    }
+                    st.current = next;
+                    drop(st);
+
+                    scroll_row_into_view(t, next, RankList::ROW_H);
+                    t.redraw();
+                    if moved {
+                        t.do_callback();
+                    }
+                    true
+                }
+                _ => false,
+            });
+            // endregion
 
-        menubar
-    }
+            RankList { inner, state }
+        }
 
-    /// Replaces highlighted text in a `TextEditor` with the text
-    /// passed in the `rpltxt` parameter.
-    pub fn fltk_replace_highlighted_text(edtr: &TextEditor, buf: &mut TextBuffer, rpltxt: &str) {
-        let (x, y) = match edtr.buffer().unwrap().selection_position() {
-            Some(position) => position,
-            None => panic!("\nError!  Could not find a cursor position in the editor.\n"),
-        };
+        /// Returns the current order as a permutation:  element `i` is the
+        /// index, in the vector passed to `new()`, of the item ranked `i + 1`.
+        pub fn order(&self) -> Vec<usize> {
+            self.state.borrow().order.clone()
+        }
 
-        buf.remove(x, y);                         // Remove the selected text
-        buf.insert(x, rpltxt);                    // Insert new text and
-        edtr.buffer().unwrap().unselect();        // Unhighlight text
+        /// Puts the items in the order given by the permutation `order`.
+        /// Does nothing if `order` isn't a permutation of the items.
+        pub fn set_order(&mut self, order: &[usize]) {
+            let mut st = self.state.borrow_mut();
+            let mut sorted = order.to_vec();
+            sorted.sort_unstable();
+            if sorted.iter().enumerate().any(|(rank, &idx)| rank != idx) || sorted.len() != st.labels.len() {
+                return;
+            }
+            st.order = order.to_vec();
+            drop(st);
+            self.inner.redraw();
+        }
     }
 
-    /// Creates a popup window that contains two buttons.
+    /// Creates a dialog for putting items in order, such as the steps of a
+    /// task or a list of priorities.  The items can be dragged with the
+    /// mouse, or moved with Alt+Up and Alt+Down.
+    /// Returns the order as a permutation -- element `i` is the index in
+    /// `labels` of the item ranked `i + 1` -- when OK is clicked, or `None`
+    /// if Cancel is clicked or the window is closed.
     ///
     /// Example:
     ///
-    ///     use fltk::{app, window};
-    ///     use fltk::enums::Color;
-    ///     use fltk::prelude::{GroupExt, WidgetBase, WidgetExt};
     ///     use lib_myfltk::fltkutils::*;
     ///
-    ///     fn main() {
-    ///         let app = app::App::default();
+    ///     let _app = fltk::app::App::default();
     ///
-    ///         let mut primwin = window::Window::new(1000, 100, 700, 850, "Two Button Popup Example");
-    ///         primwin.set_color(Color::Yellow);
-    ///         primwin.end();
-    ///         primwin.show();
+    ///     let steps: Vec<String> = ["Dry", "Rinse", "Wash"].iter().map(|s| s.to_string()).collect();
     ///
-    ///         let bttn1click = || {
-    ///             println!("\n Button 1 was clicked \n");
-    ///         };
+    ///     if let Some(order) = fltk_rank_menu(&steps, "Put the steps in order") {
+    ///         let ranked: Vec<&String> = order.iter().map(|&idx| &steps[idx]).collect();
+    ///         println!("\n ranked: {:?} \n", ranked);
+    ///     }
     ///
-    ///         let bttn2click = || {
-    ///             println!("\n Button 2 was clicked \n");
-    ///         };
+    pub fn fltk_rank_menu(labels: &[String], title: &str) -> Option<Vec<usize>> {
+
+        // region Set up the window, the hint, the list & the buttons.
+        let list_h = (labels.len() as i32 * RankList::ROW_H + 4).clamp(60, 420);
+        let mut win = Window::default().with_size(400, list_h + 95).with_label(title);
+
+        let mut hint = frame::Frame::new(10, 5, 380, 25, "Drag the items, or move them with Alt+Up and Alt+Down.");
+        hint.set_label_size(12);
+        hint.set_align(Align::Left | Align::Inside);
+
+        let mut list = RankList::new(10, 35, 380, list_h, labels.to_vec());
+
+        let mut ok = button::ReturnButton::new(200, list_h + 50, 90, 35, "OK");
+        let mut cancel = Button::new(300, list_h + 50, 90, 35, "Cancel");
+
+        win.end();
+        win.resizable(&*list);
+        win.show();
+        let _ = list.take_focus();
+        // endregion
+
+        // region Do the OK & Cancel callbacks.
+        let result: Rc<RefCell<Option<Vec<usize>>>> = Rc::new(RefCell::new(None));
+        let result_ok = result.clone();
+        let list_ok = list.clone();
+        let mut win_ok = win.clone();
+        ok.set_callback(move |_| {
+            *result_ok.borrow_mut() = Some(list_ok.order());
+            win_ok.hide();
+        });
+
+        let mut win_cancel = win.clone();
+        cancel.set_callback(move |_| win_cancel.hide());
+        // endregion
+
+        while win.shown() {
+            app::wait();
+        }
+
+        let ret = result.borrow_mut().take();
+        ret
+    }
+
+    /// Same as `fltk_rank_menu()`, but works on any slice of items.
+    /// The `label` closure gives the text for each item.
+    pub fn fltk_rank_menu_by<T, F>(items: &[T], label: F, title: &str) -> Option<Vec<usize>>
+        where F: Fn(&T) -> String
+    {
+        let labels: Vec<String> = items.iter().map(label).collect();
+        fltk_rank_menu(&labels, title)
+    }
+
+    /// Makes the 14x14 check box icon shown beside each item of
+    /// `fltk_tree_picker()`.  `Some(true)` is checked, `Some(false)` is
+    /// unchecked and `None` is partly checked.
+    fn tri_state_icon(state: Option<bool>) -> image::RgbImage {
+        const SIZE: i32 = 14;
+        let mut pixels = vec![255u8; (SIZE * SIZE * 3) as usize];
+        let mut set = |x: i32, y: i32, shade: u8| {
+            let at = ((y * SIZE + x) * 3) as usize;
+            pixels[at..at + 3].copy_from_slice(&[shade, shade, shade]);
+        };
+
+        for i in 0..SIZE {
+            set(i, 0, 90);
+            set(i, SIZE - 1, 90);
+            set(0, i, 90);
+            set(SIZE - 1, i, 90);
+        }
+        match state {
+            Some(true) => {
+                for i in 0..=3 {
+                    set(3 + i, 6 + i, 0);
+                    set(3 + i, 7 + i, 0);
+                }
+                for x in 6..=10 {
+                    let y = 10 - (x - 6) * 7 / 4;
+                    set(x, y.max(2), 0);
+                    set(x, (y - 1).max(2), 0);
+                }
+            }
+            None => {
+                for y in 4..=9 {
+                    for x in 4..=9 {
+                        set(x, y, 60);
+                    }
+                }
+            }
+            Some(false) => {}
+        }
+
+        image::RgbImage::new(&pixels, SIZE, SIZE, ColorDepth::Rgb8).unwrap()
+    }
+
+    /// The leaves of `fltk_tree_picker()` and which of them are checked.
+    struct TreePickState {
+        leaves: HashMap<String, usize>,   // Tree path -> index in the paths passed in.
+        checked: Vec<bool>,
+    }
+
+    impl TreePickState {
+        /// Returns the indices of the leaves at or under `item`.
+        fn leaves_under(&self, tree: &tree::Tree, item: &tree::TreeItem) -> Vec<usize> {
+            if item.has_children() {
+                (0..item.children())
+                    .filter_map(|i| item.child(i))
+                    .flat_map(|child| self.leaves_under(tree, &child))
+                    .collect()
+            } else {
+                tree.item_pathname(item).ok()
+                    .and_then(|path| self.leaves.get(&path).copied())
+                    .into_iter()
+                    .collect()
+            }
+        }
+
+        /// Returns `Some(true)` if every leaf at or under `item` is checked,
+        /// `Some(false)` if none are, and `None` if some are.
+        fn state_of(&self, tree: &tree::Tree, item: &tree::TreeItem) -> Option<bool> {
+            let leaves = self.leaves_under(tree, item);
+            let count = leaves.iter().filter(|&&idx| self.checked[idx]).count();
+            match count {
+                0 => Some(false),
+                n if n == leaves.len() => Some(true),
+                _ => None,
+            }
+        }
+
+        /// Gives every item the icon for its state.
+        fn refresh_icons(&self, tree: &mut tree::Tree, icons: &[image::RgbImage; 3]) {
+            for mut item in tree.get_items().unwrap_or_default() {
+                let icon = match self.state_of(tree, &item) {
+                    Some(false) => &icons[0],
+                    Some(true) => &icons[1],
+                    None => &icons[2],
+                };
+                item.set_user_icon(Some(icon.clone()));
+            }
+            tree.redraw();
+        }
+    }
+
+    /// Creates a check box tree for picking from items arranged in levels,
+    /// such as subject / chapter / section.  Each entry in `paths` is one
+    /// leaf, with its levels separated by `/`, e.g. "Math/Algebra/Factoring".
+    /// Checking a branch checks everything under it, and a branch that is
+    /// only partly checked shows a filled square.  Click an item, or press
+    /// Space on it, to check or uncheck it.  `checked` lists the indices
+    /// of the leaves that start out checked.  A path that is the start of
+    /// another path, such as "Math/Algebra" beside "Math/Algebra/Factoring",
+    /// becomes a branch and is never returned.
+    /// Returns the checked leaf paths in the order they were passed in when
+    /// OK is clicked, or `None` if Cancel is clicked or the window is closed.
     ///
-    ///         let mut popup = fltk_popup_2btn(&primwin, Box::new(bttn1click), "Button 1",
-    ///                         Box::new(bttn2click), "Button 2");
+    /// Example:
     ///
-    ///         popup.end();
-    ///         popup.show();
+    ///     use lib_myfltk::fltkutils::*;
     ///
-    ///         app.run().unwrap();
-    ///     }
+    ///     let _app = fltk::app::App::default();
     ///
+    ///     let paths: Vec<String> = [
+    ///         "Math/Algebra/Factoring",
+    ///         "Math/Algebra/Quadratics",
+    ///         "Math/Geometry/Triangles",
+    ///         "Science/Biology/Cells",
+    ///     ].iter().map(|s| s.to_string()).collect();
     ///
-    pub fn fltk_popup_2btn(primwin: &Window, mut closure1: Box<dyn FnMut() + 'static>, label1: &str,
-                           mut closure2: Box<dyn FnMut() + 'static>, label2: &str) -> Window
-    {
+    ///     if let Some(picked) = fltk_tree_picker(&paths, &[0], "Pick the sections") {
+    ///         println!("\n picked: {:?} \n", picked);
+    ///     }
+    ///
+    pub fn fltk_tree_picker(paths: &[String], checked: &[usize], title: &str) -> Option<Vec<String>> {
+
+        // region Set up the window, the tree & the buttons.
+        let mut win = Window::default().with_size(420, 560).with_label(title);
+
+        let mut tree = tree::Tree::new(10, 10, 400, 480, None);
+        tree.set_show_root(false);
+        tree.set_select_mode(tree::TreeSelect::None);  // Checking is done by hand below.
+        let added: Vec<(usize, tree::TreeItem)> = paths.iter().enumerate()
+            .filter_map(|(idx, path)| tree.add(path).map(|item| (idx, item)))
+            .collect();
+
+        // A path that another one runs through, such as "a/b" beside
+        // "a/b/c", turns into a branch, so it isn't kept as a leaf.
+        let mut leaves = HashMap::new();
+        for (idx, item) in added {
+            if item.has_children() {
+                continue;
+            }
+            if let Ok(full) = tree.item_pathname(&item) {
+                leaves.entry(full).or_insert(idx);  // A repeated path stays with its first index.
+            }
+        }
 
-        // region Calculate the window position -- tied to the primary window.
-        let win_center = fltk_find_center_wndw(primwin);
-        let popwidth = 575;  // popwidth & popheight are set to accomodate the size of the buttons.
-        let popheight = 100;
+        let mut count = frame::Frame::new(10, 505, 200, 35, None);
+        count.set_align(Align::Left | Align::Inside);
+        let mut ok = button::ReturnButton::new(230, 505, 85, 35, "OK");
+        let mut cancel = Button::new(325, 505, 85, 35, "Cancel");
 
-        let xxx = win_center.0 - popwidth / 2;
-        let yyy = win_center.1 - popheight / 2;
+        win.end();
+        win.resizable(&tree);
+        win.show();
         // endregion
 
-        // region Create the popup window with buttons
-        let popwin = Window::default().with_size(popwidth, popheight).with_pos(xxx, yyy);
+        // region Start with the requested leaves checked.
+        let mut start = vec![false; paths.len()];
+        for &idx in checked {
+            if idx < start.len() {
+                start[idx] = true;
+            }
+        }
+        let state = Rc::new(RefCell::new(TreePickState { leaves, checked: start }));
+        let icons = Rc::new([tri_state_icon(Some(false)), tri_state_icon(Some(true)), tri_state_icon(None)]);
 
-        let mut but1 = Button::new(25, 25, 250, 40, label1);
-        let mut but2 = Button::new(300, 25, 250, 40, label2);
+        let total = state.borrow().leaves.len();
+        let show_count = move |st: &TreePickState, count: &mut frame::Frame| {
+            let picked = st.leaves.values().filter(|&&idx| st.checked[idx]).count();
+            count.set_label(&format!("{} of {} picked", picked, total));
+        };
+        state.borrow().refresh_icons(&mut tree, &icons);
+        show_count(&state.borrow(), &mut count);
         // endregion
 
-        // region Do the button callbacks
-        let mut winclone1 = popwin.clone();
-        but1.set_callback(move |_| {
-            closure1();
-            winclone1.hide();
+        // region Check or uncheck an item & everything under it when it is clicked or gets a Space.
+        // The arrow keys are left to the tree, so they only move the focus.
+        let state_pick = state.clone();
+        let icons_pick = icons.clone();
+        let mut count_pick = count.clone();
+        tree.handle(move |t, ev| {
+            let item = match ev {
+                Event::Push => {
+                    let Some(item) = t.find_clicked(false) else { return false };
+                    // Leave clicks on the open / close box to the tree.
+                    if app::event_x() < item.label_x() - icons_pick[0].w() - t.user_icon_margin_left() {
+                        return false;
+                    }
+                    let _ = t.take_focus();
+                    t.set_item_focus(&item);
+                    item
+                }
+                Event::KeyDown if app::event_key() == Key::from_char(' ') => {
+                    let Some(item) = t.get_item_focus() else { return false };
+                    item
+                }
+                _ => return false,
+            };
+            let mut st = state_pick.borrow_mut();
+            let turn_on = st.state_of(t, &item) != Some(true);
+            for idx in st.leaves_under(t, &item) {
+                st.checked[idx] = turn_on;
+            }
+            st.refresh_icons(t, &icons_pick);
+            show_count(&st, &mut count_pick);
+            true
         });
+        // endregion
 
-        let mut winclone2 = popwin.clone();
-        but2.set_callback(move |_| {
-            closure2();
-            winclone2.hide();
+        // region Do the OK & Cancel callbacks.
+        let result: Rc<RefCell<Option<Vec<String>>>> = Rc::new(RefCell::new(None));
+        let result_ok = result.clone();
+        let state_ok = state.clone();
+        let paths_ok = paths.to_vec();
+        let mut win_ok = win.clone();
+        ok.set_callback(move |_| {
+            let st = state_ok.borrow();
+            let mut picked: Vec<usize> = st.leaves.values().copied().filter(|&idx| st.checked[idx]).collect();
+            picked.sort_unstable();
+            *result_ok.borrow_mut() = Some(picked.into_iter().map(|idx| paths_ok[idx].clone()).collect());
+            win_ok.hide();
         });
+
+        let mut win_cancel = win.clone();
+        cancel.set_callback(move |_| win_cancel.hide());
         // endregion
 
-        popwin
+        while win.shown() {
+            app::wait();
+        }
+
+        let ret = result.borrow_mut().take();
+        ret
     }
 
-    /// Returns the coordinates of the center of `win`.
+    /// Settings for `fltk_radio_menu_with()`.
     ///
-    pub fn fltk_find_center_wndw(win: &Window) -> (i32, i32) {
-        let xxx = win.x();
-        let yyy = win.y();
-        let www = win.w();
-        let hhh = win.h();
+    #[derive(Debug, Clone, Default)]
+    pub struct RadioMenuOptions {
+        /// The window title.
+        pub prompt: String,
+        /// The index of the button that starts out chosen.
+        pub default: Option<usize>,
+    }
 
-        // Calculate the center position of primwin
-        let center_x = xxx + www / 2;
-        let center_y = yyy + hhh / 2;
+    impl RadioMenuOptions {
+        /// Sets the window title.
+        pub fn with_prompt(mut self, prompt: &str) -> Self {
+            self.prompt = prompt.to_string();
+            self
+        }
 
-        (center_x, center_y)
+        /// Sets the index of the button that starts out chosen.
+        pub fn with_default(mut self, default: usize) -> Self {
+            self.default = Some(default);
+            self
+        }
     }
-    
-    /// Returns the dimensions of a button given a label.
+
+    /// Creates a radio button menu set up from `opts`.  The default button
+    /// starts out chosen.  Up and Down move the choice, Enter submits it
+    /// and Escape cancels.  Typing in the Find field narrows the list.
+    /// The window is sized to fit the labels, like `fltk_checkbox_menu_with()`.
+    /// Returns the index of the chosen button, or `None` if nothing was
+    /// chosen, Cancel was clicked or the window was closed.
     ///
-    ///     #Example for  fltk_size_bttn_to_fit_label  &  fltk_center_button_in_win
-    /// 
-    ///      // The goal is to display a button centered in a window with both sized dynamically.
+    /// Example:
     ///
-    ///     use fltk::{prelude::*, app, window::Window};
-    ///     use fltk::app::{set_font, set_font_size};
-    ///     use fltk::{button::Button, enums::Font};
-    ///     use lib_myfltk::fltkutils::{fltk_center_button_in_win, fltk_size_bttn_to_fit_label};
+    ///     use lib_myfltk::fltkutils::*;
     ///
-    ///     const FONT: Font = Font::Helvetica;
-    ///     const FONT_SIZE: i32 = 20;
-    ///     const BTTN_PADDING: i32 = 30;
+    ///     let _app = fltk::app::App::default();
     ///
-    ///     fn main() {
-    ///         let app = app::App::default();
+    ///     let labels = vec!["flamingo".to_string(), "tiger".to_string(), "lion".to_string()];
+    ///     let opts = RadioMenuOptions::default()
+    ///         .with_prompt("Pick an animal")
+    ///         .with_default(1);
     ///
-    ///         set_font(FONT);
-    ///         set_font_size(FONT_SIZE);   // Set font & size to whatever you like best.
+    ///     match fltk_radio_menu_with(&labels, &opts) {
+    ///         Some(idx) => println!("\n choice: {} \n", labels[idx]),
+    ///         None => println!("\n No choice was made. \n"),
+    ///     }
     ///
-    ///        //let mut win1 = Window::default().with_size(40, 20);
-    ///        let label = "123113333zzzzzzzzzzzffffffffffhhhhhhhhhhsdsdfdsfhj";
+    pub fn fltk_radio_menu_with(labels: &[String], opts: &RadioMenuOptions) -> Option<usize> {
+        radio_menu_index(labels, opts)
+    }
+
+    /// Same as `fltk_radio_lightbtn_menu()`, but works on any slice of
+    /// items and returns the index of the chosen item.  Returns `None` if
+    /// nothing was chosen, Cancel was clicked or the window was closed.
+    /// The `label` closure gives the text for each item's button.
+    pub fn fltk_radio_menu_by<T, F>(items: &[T], label: F, prompt: &str) -> Option<usize>
+        where F: Fn(&T) -> String
+    {
+        let labels: Vec<String> = items.iter().map(label).collect();
+        radio_menu_index(&labels, &RadioMenuOptions::default().with_prompt(prompt))
+    }
+
+    /// Same as `fltk_radio_menu_by()`, but returns a reference to the
+    /// chosen item instead of its index.
+    pub fn fltk_radio_menu_item<'a, T, F>(items: &'a [T], label: F, prompt: &str) -> Option<&'a T>
+        where F: Fn(&T) -> String
+    {
+        fltk_radio_menu_by(items, label, prompt).map(|idx| &items[idx])
+    }
+
+    /// Runs a radio button menu and returns the index of the chosen button.
+    fn radio_menu_index(labels: &[String], opts: &RadioMenuOptions) -> Option<usize> {
+
+        // region Set up the window & the scrolling list of radio buttons.
+        // Each row needs room for the light, the pack's indent & the scrollbar.
+        let layout = MenuLayout::fit(labels, 75, 270, 130, 60);
+        let (list_w, list_h) = (layout.list_w, layout.list_h);
+        let mut win = Window::default().with_size(list_w + 130, list_h + 60).with_label(&opts.prompt);
+
+        let scroll = group::Scroll::new(10, 50, list_w, list_h, None);
+        let pack = group::Pack::new(15, 55, list_w - 30, labels.len() as i32 * MenuLayout::ROW_H, None);
+        let mut radios = Vec::new();
+        for (idx, (label, shown)) in labels.iter().zip(&layout.shown).enumerate() {
+            let mut radio = button::RadioLightButton::default()
+                .with_label(shown)
+                .with_size(0, MenuLayout::ROW_H);
+            if shown != label {
+                radio.set_tooltip(label);
+            }
+            radio.clear_visible_focus();  // Keep the focus in the Find field, where the arrow keys are read.
+            radio.set_value(opts.default == Some(idx));
+            radios.push(radio);
+        }
+        pack.end();
+        scroll.end();
+
+        let mut submit = button::ReturnButton::new(list_w + 25, 50 + list_h / 2 - 40, 95, 35, "Submit");
+        let mut cancel = Button::new(list_w + 25, 50 + list_h / 2 + 5, 95, 35, "Cancel");
+
+        // Hidden buttons stay toggled, so the choice survives filtering.
+        let mut radios_fltr = radios.clone();
+        let labels_fltr = labels.to_vec();
+        let mut scroll_fltr = scroll.clone();
+        let mut filter = add_filter_row(10, 10, list_w + 110, move |query, mode| {
+            filter_buttons(&mut radios_fltr, &labels_fltr, query, mode, &mut scroll_fltr);
+        });
+
+        win.end();
+        win.show();
+        let _ = filter.take_focus();
+        // endregion
+
+        // region Move the choice between the showing buttons with Up & Down.
+        let mut radios_keys = radios.clone();
+        let mut scroll_keys = scroll.clone();
+        filter.handle(move |_, ev| {
+            if ev != Event::KeyDown {
+                return false;
+            }
+            let down = match app::event_key() {
+                Key::Up => false,
+                Key::Down => true,
+                _ => return false,
+            };
+            let showing: Vec<usize> = (0..radios_keys.len()).filter(|&idx| radios_keys[idx].visible()).collect();
+            let now = showing.iter().position(|&idx| radios_keys[idx].is_toggled());
+            let next = match (now, down) {
+                (None, true) => showing.first(),
+                (None, false) => showing.last(),
+                (Some(pos), true) => showing.get(pos + 1).or(showing.last()),
+                (Some(pos), false) => showing.get(pos.saturating_sub(1)),
+            };
+            if let Some(&idx) = next {
+                for (pos, radio) in radios_keys.iter_mut().enumerate() {
+                    radio.set_value(pos == idx);
+                }
+
+                // Scroll just far enough to keep the chosen button in view.
+                let radio = &radios_keys[idx];
+                let above = scroll_keys.y() + 2 - radio.y();
+                let below = radio.y() + radio.h() - (scroll_keys.y() + scroll_keys.h() - 2);
+                if above > 0 {
+                    scroll_keys.scroll_to(0, (scroll_keys.yposition() - above).max(0));
+                } else if below > 0 {
+                    scroll_keys.scroll_to(0, scroll_keys.yposition() + below);
+                }
+                scroll_keys.redraw();
+            }
+            true
+        });
+        // endregion
+
+        // region Use the Submit callback to get the index of the selected radio button.
+        let chosen: Rc<RefCell<Option<usize>>> = Rc::new(RefCell::new(None));
+        let chosen_clone = chosen.clone();
+        let mut win_clone = win.clone();
+        submit.set_callback(move |_| {
+            *chosen_clone.borrow_mut() = radios.iter().position(|radio| radio.is_toggled());
+            win_clone.hide();
+        });
+
+        let mut win_cancel = win.clone();
+        cancel.set_callback(move |_| win_cancel.hide());
+
+        while win.shown() {
+            app::wait();
+        }
+        // endregion
+
+        let ret = *chosen.borrow();
+        ret
+    }
+
+    /// How the Find text of the check box and radio menus is matched against the labels.
     ///
-    ///        // Find the dimensions of the button label.
-    ///        let (bttn_width, bttn_height) = fltk_size_bttn_to_fit_label(&label);
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum MatchMode {
+        /// The label contains the filter text, matching case.
+        Substring,
+        /// The label contains the filter text, ignoring case.
+        CaseInsensitive,
+        /// The letters of the filter text appear in the label in order,
+        /// though not necessarily side by side.  Ignores case.
+        Fuzzy,
+    }
+
+    impl MatchMode {
+        /// Returns true if `label` matches the filter text `query`.
+        /// An empty query matches everything.
+        ///
+        /// Example:
+        ///
+        ///     use lib_myfltk::fltkutils::MatchMode;
+        ///
+        ///     assert!(MatchMode::CaseInsensitive.matches("alg", "Algebra 1"));
+        ///     assert!(!MatchMode::Substring.matches("alg", "Algebra 1"));
+        ///     assert!(MatchMode::Fuzzy.matches("agb1", "Algebra 1"));
+        ///
+        pub fn matches(&self, query: &str, label: &str) -> bool {
+            match self {
+                MatchMode::Substring => label.contains(query),
+                MatchMode::CaseInsensitive => label.to_lowercase().contains(&query.to_lowercase()),
+                MatchMode::Fuzzy => {
+                    let mut letters = label.chars().flat_map(|c| c.to_lowercase());
+                    query.chars()
+                        .flat_map(|c| c.to_lowercase())
+                        .all(|q| letters.any(|l| l == q))
+                }
+            }
+        }
+    }
+
+    /// The labels and checked state behind a `VirtualChecklist`.
+    /// Checked state is kept in a bitset, one bit per item, so it survives
+    /// the item being hidden by the filter.
+    struct ChecklistState {
+        labels: Vec<String>,
+        bits: Vec<u64>,
+        shown: Vec<usize>,   // The items that pass the filter, by row.
+        current: usize,      // A row, not an item.
+        single: bool,
+    }
+
+    impl ChecklistState {
+        /// Every item unchecked and showing.
+        fn new(labels: Vec<String>) -> Self {
+            let count = labels.len();
+            ChecklistState {
+                labels,
+                bits: vec![0; count / 64 + 1],
+                shown: (0..count).collect(),
+                current: 0,
+                single: false,
+            }
+        }
+
+        /// Shows only the items whose labels match `query`.
+        fn filter(&mut self, query: &str, mode: MatchMode) {
+            self.shown = (0..self.labels.len())
+                .filter(|&idx| mode.matches(query, &self.labels[idx]))
+                .collect();
+            self.current = 0;
+        }
+
+        /// The indices of the checked items, in input order.
+        fn checked(&self) -> Vec<usize> {
+            (0..self.labels.len()).filter(|&idx| self.is_checked(idx)).collect()
+        }
+
+        fn is_checked(&self, idx: usize) -> bool {
+            self.bits[idx / 64] & (1u64 << (idx % 64)) != 0
+        }
+
+        fn set_checked(&mut self, idx: usize, checked: bool) {
+            if checked {
+                self.bits[idx / 64] |= 1u64 << (idx % 64);
+            } else {
+                self.bits[idx / 64] &= !(1u64 << (idx % 64));
+            }
+        }
+
+        /// Flips item `idx`.  In single mode checking an item unchecks the rest.
+        fn toggle(&mut self, idx: usize) {
+            let checked = self.is_checked(idx);
+            if self.single {
+                self.bits.iter_mut().for_each(|word| *word = 0);
+            }
+            self.set_checked(idx, !checked);
+        }
+    }
+
+    /// A scrolling list of check boxes that can hold many thousands of
+    /// items.  It is built on FLTK's `Table`, which only draws the rows
+    /// that are on screen, so no widget is created per item.
     ///
-    ///        let mut win = Window::default().with_size(400, 200);
+    /// Click a row or press Space to check it.  Up, Down, Page Up,
+    /// Page Down, Home and End move between rows.  `set_filter()` hides
+    /// the items that don't match some filter text; hidden items keep
+    /// their checked state.  `set_single()` turns the list into a radio
+    /// list where only one item can be checked.
     ///
-    ///        // Set the dimensions of the window to fit the button.
-    ///        // (Note that the window can be any size you like as long as the button fits.)
-    ///        let win_width = bttn_width + BTTN_PADDING * 2;
-    ///        let win_height = bttn_height + BTTN_PADDING * 2;
-    ///        win.set_size(win_width, win_height);
+    /// Example:
     ///
-    ///        // Create the button.
-    ///        let mut bttn = Button::default().with_size(bttn_width, bttn_height).with_label(label);
+    ///     use fltk::{prelude::*, *};
+    ///     use lib_myfltk::fltkutils::VirtualChecklist;
     ///
-    ///        // Center the button in the window.
-    ///        let (xxx, yyy) = fltk_center_button_in_win(&win, &bttn);
-    ///        bttn.set_pos(xxx, yyy);
+    ///     let app = app::App::default();
+    ///     let mut win = window::Window::default().with_size(400, 500);
     ///
-    ///        win.end();
-    ///        win.show();
+    ///     let labels: Vec<String> = (1..=10_000).map(|n| format!("Question {}", n)).collect();
+    ///     let list = VirtualChecklist::new(10, 10, 380, 480, labels);
     ///
-    ///        app.run().unwrap();
-    ///     }
+    ///     win.end();
+    ///     win.show();
+    ///     app.run().unwrap();
     ///
-     pub fn fltk_size_bttn_to_fit_label(label: &str) -> (i32, i32) {
+    ///     println!("\n checked: {:?} \n", list.checked());
+    ///
+    #[derive(Clone)]
+    pub struct VirtualChecklist {
+        inner: table::Table,
+        state: Rc<RefCell<ChecklistState>>,
+    }
 
-        const FONT: Font = Font::Helvetica;
-        const BTTN_LABEL_PADDING: i32 = 10;
+    fltk::widget_extends!(VirtualChecklist, table::Table, inner);
+
+    impl VirtualChecklist {
+        const ROW_H: i32 = 26;
+
+        /// Creates the list with every item unchecked.
+        pub fn new(x: i32, y: i32, w: i32, h: i32, labels: Vec<String>) -> Self {
+            let count = labels.len();
+            let state = Rc::new(RefCell::new(ChecklistState::new(labels)));
+
+            // region Set up the table -- one column, one row per item.
+            let mut inner = table::Table::new(x, y, w, h, None);
+            inner.set_rows(count as i32);
+            inner.set_cols(1);
+            inner.set_row_header(false);
+            inner.set_col_header(false);
+            inner.set_row_height_all(VirtualChecklist::ROW_H);
+            inner.set_col_width_all(w - 20);  // Leave room for the scrollbar.
+            inner.end();
+            // endregion
+
+            // region Draw only the rows the table asks for.
+            let state_draw = state.clone();
+            inner.draw_cell(move |t, ctx, row, _col, x, y, w, h| {
+                if ctx != table::TableContext::Cell {
+                    return;
+                }
+                let st = state_draw.borrow();
+                let row = row as usize;
+                if let Some(&idx) = st.shown.get(row) {
+                    VirtualChecklist::draw_row(&st, idx, row == st.current && t.has_focus(), x, y, w, h);
+                }
+            });
+            // endregion
+
+            // region Check a row when it is clicked.
+            let state_click = state.clone();
+            inner.set_callback(move |t| {
+                if t.callback_context() == table::TableContext::Cell {
+                    let row = t.callback_row() as usize;
+                    let mut st = state_click.borrow_mut();
+                    if let Some(&idx) = st.shown.get(row) {
+                        st.current = row;
+                        st.toggle(idx);
+                    }
+                    drop(st);
+                    t.redraw();
+                }
+            });
+            // endregion
+
+            // region Move between rows & check them from the keyboard.
+            let state_keys = state.clone();
+            inner.handle(move |t, ev| match ev {
+                Event::Focus | Event::Unfocus => {
+                    t.redraw();
+                    true
+                }
+                Event::KeyDown => {
+                    let mut st = state_keys.borrow_mut();
+                    let count = st.shown.len();
+                    if count == 0 {
+                        return false;
+                    }
+                    let page = rows_per_page(t, VirtualChecklist::ROW_H);
+                    let current = st.current.min(count - 1);
+                    let next = match row_for_key(app::event_key(), current, count, page) {
+                        Some(next) => next,
+                        None if app::event_text() == " " => {
+                            let idx = st.shown[current];
+                            st.toggle(idx);
+                            current
+                        }
+                        None => return false,
+                    };
+                    st.current = next;
+                    drop(st);
+
+                    scroll_row_into_view(t, next, VirtualChecklist::ROW_H);
+                    t.redraw();
+                    true
+                }
+                _ => false,
+            });
+            // endregion
 
-        let mut win = Window::default().with_size(40, 20);
+            VirtualChecklist { inner, state }
+        }
 
-        win.show();   // Show the window so you can measure the label.  Note that
-        // the only reason you need to show the window is to measure the label.
-        // The size of the window doesn't matter as far as the button is concerned.
-        draw::set_font(FONT, app::font_size());  // Also needed so you can measure the label.
-        let (label_len, label_height) = measure(&label, false);
+        /// Draws item `idx`:  the check box (or radio circle), its mark and the label.
+        fn draw_row(st: &ChecklistState, idx: usize, current: bool, x: i32, y: i32, w: i32, h: i32) {
+            let (label, checked, single) = (&st.labels[idx], st.is_checked(idx), st.single);
+            draw::push_clip(x, y, w, h);
+            let bg = if current { Color::from_rgb(220, 230, 245) } else { Color::Background2 };
+            draw::draw_rect_fill(x, y, w, h, bg);
+
+            let bx = x + 4;
+            let by = y + (h - 14) / 2;
+            draw::set_draw_color(Color::Foreground);
+            if single {
+                draw::draw_box(FrameType::RoundDownBox, bx, by, 14, 14, Color::Background2);
+                if checked {
+                    draw::set_draw_color(Color::Foreground);
+                    draw::draw_pie(bx + 4, by + 4, 6, 6, 0.0, 360.0);
+                }
+            } else {
+                draw::draw_box(FrameType::DownBox, bx, by, 14, 14, Color::Background2);
+                if checked {
+                    draw::set_draw_color(Color::Foreground);
+                    draw::set_line_style(draw::LineStyle::Solid, 2);
+                    draw::draw_line(bx + 3, by + 7, bx + 6, by + 10);
+                    draw::draw_line(bx + 6, by + 10, bx + 11, by + 3);
+                    draw::set_line_style(draw::LineStyle::Solid, 0);
+                }
+            }
+
+            draw::set_draw_color(Color::Foreground);
+            draw::set_font(Font::Helvetica, app::font_size());
+            draw::draw_text2(label, bx + 22, y, w - 26, h, Align::Left);
+
+            if current {
+                draw::set_line_style(draw::LineStyle::Dot, 1);
+                draw::draw_rect(x + 1, y + 1, w - 2, h - 2);
+                draw::set_line_style(draw::LineStyle::Solid, 0);
+            }
+            draw::pop_clip();
+        }
+
+        /// Turns single mode on or off.  In single mode checking an item
+        /// unchecks the others, like a set of radio buttons.
+        pub fn set_single(&mut self, single: bool) {
+            self.state.borrow_mut().single = single;
+            self.inner.redraw();
+        }
+
+        /// Shows only the items whose labels match `query`.  An empty query
+        /// shows everything.  Hidden items keep their checked state.
+        pub fn set_filter(&mut self, query: &str, mode: MatchMode) {
+            let mut st = self.state.borrow_mut();
+            st.filter(query, mode);
+            let rows = st.shown.len() as i32;
+            drop(st);
+
+            self.inner.set_rows(rows);
+            self.inner.set_row_position(0);
+            self.inner.redraw();
+        }
+
+        /// Returns the number of items that pass the filter.
+        pub fn shown_count(&self) -> usize {
+            self.state.borrow().shown.len()
+        }
+
+        /// Returns true if item `idx` is checked.
+        pub fn is_checked(&self, idx: usize) -> bool {
+            self.state.borrow().is_checked(idx)
+        }
+
+        /// Checks or unchecks item `idx`.
+        pub fn set_checked(&mut self, idx: usize, checked: bool) {
+            let mut st = self.state.borrow_mut();
+            if checked && st.single {
+                st.bits.iter_mut().for_each(|word| *word = 0);
+            }
+            st.set_checked(idx, checked);
+            drop(st);
+            self.inner.redraw();
+        }
+
+        /// Checks or unchecks every item that passes the filter.
+        pub fn set_all(&mut self, checked: bool) {
+            let mut st = self.state.borrow_mut();
+            let shown = st.shown.clone();
+            for idx in shown {
+                st.set_checked(idx, checked);
+            }
+            drop(st);
+            self.inner.redraw();
+        }
+
+        /// Returns the indices of the checked items, in input order.
+        /// Items hidden by the filter are included.
+        pub fn checked(&self) -> Vec<usize> {
+            self.state.borrow().checked()
+        }
+    }
+
+    /// The number of whole rows of height `row_h` that fit in a `Table`,
+    /// less one so a part-hidden last row doesn't count.
+    fn rows_per_page(t: &table::Table, row_h: i32) -> usize {
+        (t.h() / row_h - 1).max(1) as usize
+    }
+
+    /// Where a key moves the current row of a `Table`-based list of `count`
+    /// rows, `page` of which fit on screen.  Returns `None` for keys that
+    /// don't move it.  `count` must not be zero.
+    fn row_for_key(key: Key, current: usize, count: usize, page: usize) -> Option<usize> {
+        match key {
+            Key::Up => Some(current.saturating_sub(1)),
+            Key::Down => Some((current + 1).min(count - 1)),
+            Key::PageUp => Some(current.saturating_sub(page)),
+            Key::PageDown => Some((current + page).min(count - 1)),
+            Key::Home => Some(0),
+            Key::End => Some(count - 1),
+            _ => None,
+        }
+    }
+
+    /// The top row that shows `row`, scrolling as little as possible from `top`.
+    fn top_row_for(row: usize, top: usize, page: usize) -> usize {
+        if row < top {
+            row
+        } else if row >= top + page {
+            row + 1 - page
+        } else {
+            top
+        }
+    }
+
+    /// Scrolls a `Table` of `row_h` high rows just far enough to show `row`.
+    fn scroll_row_into_view(t: &mut table::Table, row: usize, row_h: i32) {
+        let top = t.row_position().max(0) as usize;
+        t.set_row_position(top_row_for(row, top, rows_per_page(t, row_h)) as i32);
+    }
+
+    /// Adds the Find field and match-mode chooser that sit above the list
+    /// in the check box and radio menus.  `on_change` is called with the
+    /// filter text and mode each time either one changes.
+    fn add_filter_row<F>(x: i32, y: i32, w: i32, on_change: F) -> input::Input
+        where F: FnMut(&str, MatchMode) + 'static
+    {
+        let on_change = RefCell::new(on_change);
+        let mut filter = input::Input::new(x + 45, y, w - 185, 30, "Find");
+        let mut mode = menu::Choice::new(x + w - 130, y, 130, 30, None);
+        mode.add_choice("Contains|Ignore case|Fuzzy");
+        mode.set_value(1);
+
+        let modes = [MatchMode::Substring, MatchMode::CaseInsensitive, MatchMode::Fuzzy];
+        let refilter = Rc::new({
+            let filter = filter.clone();
+            let mode = mode.clone();
+            move || {
+                let picked = modes[mode.value().clamp(0, 2) as usize];
+                (on_change.borrow_mut())(&filter.value(), picked);
+            }
+        });
+
+        filter.set_trigger(CallbackTrigger::Changed);
+        let refilter_fltr = refilter.clone();
+        filter.set_callback(move |_| refilter_fltr());
+        mode.set_callback(move |_| refilter());
+
+        filter
+    }
+
+    /// Shows the buttons whose labels match the filter and hides the rest.
+    /// A pack skips hidden children, so the matches close up; buttons
+    /// placed by `MenuLayout::place()` need placing again afterwards.
+    fn filter_buttons<B: WidgetExt>(buttons: &mut [B], labels: &[String], query: &str, mode: MatchMode,
+                                    scroll: &mut group::Scroll) {
+        for (button, label) in buttons.iter_mut().zip(labels) {
+            if mode.matches(query, label) {
+                button.show();
+            } else {
+                button.hide();
+            }
+        }
+        scroll.scroll_to(0, 0);
+        scroll.redraw();
+    }
+
+    /// Creates a check box menu that stays quick with thousands of items.
+    /// It uses a `VirtualChecklist`, so only the rows on screen are drawn.
+    /// Typing in the Find field narrows the list; checked items that are
+    /// filtered out stay checked.  "Select all" and "Select none" work on
+    /// the items that are showing.
+    /// Returns the indices of the checked items in input order, or an
+    /// empty vector if the window is closed.
+    ///
+    /// Example:
+    ///
+    ///     use lib_myfltk::fltkutils::*;
+    ///
+    ///     let app = fltk::app::App::default();
+    ///
+    ///     let labels: Vec<String> = (1..=3000).map(|n| format!("Question {}", n)).collect();
+    ///     let picked = fltk_checkbox_list_menu(&app, &labels, "Pick the questions");
+    ///
+    ///     println!("\n picked: {:?} \n", picked);
+    ///
+    pub fn fltk_checkbox_list_menu(app: &App, labels: &[String], title: &str) -> Vec<usize> {
+
+        // region Set up the window, the filter, the list & the buttons.
+        let mut win = Window::default().with_size(420, 600).with_label(title);
+        let list = VirtualChecklist::new(10, 50, 400, 480, labels.to_vec());
+        let mut list_fltr = list.clone();
+        let mut filter = add_filter_row(10, 10, 400, move |query, mode| list_fltr.set_filter(query, mode));
+        let mut selectall = Button::new(10, 550, 110, 35, "Select all");
+        let mut selectnone = Button::new(130, 550, 110, 35, "Select none");
+        let mut submit = Button::new(330, 550, 80, 35, "Submit");
+        win.end();
+        win.resizable(&*list);
+        win.show();
+        let _ = filter.take_focus();
+        // endregion
+
+        // region Do the button callbacks.
+        let mut list_all = list.clone();
+        selectall.set_callback(move |_| list_all.set_all(true));
+
+        let mut list_none = list.clone();
+        selectnone.set_callback(move |_| list_none.set_all(false));
+
+        let checked: Rc<RefCell<Vec<usize>>> = Rc::new(RefCell::new(Vec::new()));
+        let checked_clone = checked.clone();
+        let list_submit = list.clone();
+        let mut win_clone = win.clone();
+        submit.set_callback(move |_| {
+            *checked_clone.borrow_mut() = list_submit.checked();
+            win_clone.hide();
+        });
+        // endregion
+
+        while win.shown() {
+            if !app.wait() {
+                break;
+            }
+        }
+
+        let retvec = take(&mut *checked.borrow_mut());
+        retvec
+    }
+
+    /// The radio button partner of `fltk_checkbox_list_menu()`.  Only one
+    /// item can be chosen, and typing in the Find field narrows the list.
+    /// Returns the index of the chosen item, or `None` if nothing was
+    /// chosen or the window was closed.
+    ///
+    pub fn fltk_radio_list_menu(app: &App, labels: &[String], prompt: &str) -> Option<usize> {
+
+        // region Set up the window, the filter, the list & the Submit button.
+        let mut win = Window::default().with_size(420, 600).with_label(prompt);
+        let mut list = VirtualChecklist::new(10, 50, 400, 480, labels.to_vec());
+        list.set_single(true);
+        let mut list_fltr = list.clone();
+        let mut filter = add_filter_row(10, 10, 400, move |query, mode| list_fltr.set_filter(query, mode));
+        let mut submit = Button::new(330, 550, 80, 35, "Submit");
+        win.end();
+        win.resizable(&*list);
+        win.show();
+        let _ = filter.take_focus();
+        // endregion
+
+        let chosen: Rc<RefCell<Option<usize>>> = Rc::new(RefCell::new(None));
+        let chosen_clone = chosen.clone();
+        let list_submit = list.clone();
+        let mut win_clone = win.clone();
+        submit.set_callback(move |_| {
+            *chosen_clone.borrow_mut() = list_submit.checked().first().copied();
+            win_clone.hide();
+        });
+
+        while win.shown() {
+            if !app.wait() {
+                break;
+            }
+        }
+
+        let ret = *chosen.borrow();
+        ret
+    }
+
+    /// Creates a simple, no-frills editor using FLTK's TextEditor struct.
+    /// Same as `fltk_simple_editor_with()` with just a starting text and a
+    /// window label.
+    /// Returns the final contents of the editor.
+    pub fn fltk_simple_editor(startertxt: &str, winlabel: &str) -> String {
+        fltk_simple_editor_with(&EditorOptions::default()
+            .with_title(winlabel)
+            .with_text(startertxt))
+    }
+
+    /// Settings for `fltk_simple_editor_with()`.
+    ///
+    #[derive(Debug, Clone)]
+    pub struct EditorOptions {
+        /// The window title.  The file name and a `*` for unsaved changes are added to it.
+        pub title: String,
+        /// The starting text, used when there is no `path`.
+        pub text: String,
+        /// A file to edit.  It is read when the editor opens, and File/Save writes to it.
+        pub path: Option<PathBuf>,
+        /// Syntax highlighting for the text, if any.
+        pub highlight: Option<SyntaxRules>,
+        /// Show line numbers down the left side.
+        pub line_numbers: bool,
+        /// Show the line, column, selection length & word count under the text.
+        pub status_bar: bool,
+    }
+
+    impl Default for EditorOptions {
+        fn default() -> Self {
+            EditorOptions {
+                title: "Editor".to_string(),
+                text: String::new(),
+                path: None,
+                highlight: None,
+                line_numbers: false,
+                status_bar: false,
+            }
+        }
+    }
+
+    impl EditorOptions {
+        /// Sets the window title.
+        pub fn with_title(mut self, title: &str) -> Self {
+            self.title = title.to_string();
+            self
+        }
+
+        /// Sets the starting text.
+        pub fn with_text(mut self, text: &str) -> Self {
+            self.text = text.to_string();
+            self
+        }
+
+        /// Sets the file to edit.
+        pub fn with_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+            self.path = Some(path.as_ref().to_path_buf());
+            self
+        }
+
+        /// Turns on syntax highlighting with `rules`, e.g. `SyntaxRules::markdown()`.
+        pub fn with_highlight(mut self, rules: SyntaxRules) -> Self {
+            self.highlight = Some(rules);
+            self
+        }
+
+        /// Turns the line-number gutter on or off.
+        pub fn with_line_numbers(mut self, on: bool) -> Self {
+            self.line_numbers = on;
+            self
+        }
+
+        /// Turns the status bar on or off.
+        pub fn with_status_bar(mut self, on: bool) -> Self {
+            self.status_bar = on;
+            self
+        }
+    }
+
+    /// The file behind a `fltk_simple_editor_with()` window and whether it
+    /// has unsaved changes.
+    struct EditorFile {
+        win: Window,
+        title: String,
+        path: Option<PathBuf>,
+        start: String,      // What Revert goes back to when there is no file.
+        dirty: bool,
+        history: EditHistory,
+    }
+
+    impl EditorFile {
+        /// Marks the text as changed or not and shows it in the window title.
+        fn set_dirty(&mut self, dirty: bool) {
+            self.dirty = dirty;
+            let star = if dirty { "*" } else { "" };
+            let label = match self.path.as_ref().and_then(|path| path.file_name()) {
+                Some(name) => format!("{}{} - {}", star, name.to_string_lossy(), self.title),
+                None => format!("{}{}", star, self.title),
+            };
+            self.win.set_label(&label);
+        }
+
+        /// Reads `path` into the buffer.  Shows an alert and returns false if it can't.
+        fn load(&mut self, buf: &mut TextBuffer, path: &Path) -> bool {
+            match fs::read_to_string(path) {
+                Ok(text) => {
+                    buf.set_text(&text);
+                    self.history.clear();
+                    self.path = Some(path.to_path_buf());
+                    self.set_dirty(false);
+                    true
+                }
+                Err(err) => {
+                    dialog::alert_default(&format!("Could not open {}\n{}", path.display(), err));
+                    false
+                }
+            }
+        }
+
+        /// Writes the buffer to the file, asking for a file name first if
+        /// there isn't one or `save_as` is true.  Returns false if the
+        /// user cancels or the file can't be written.
+        fn save(&mut self, buf: &TextBuffer, save_as: bool) -> bool {
+            let path = match &self.path {
+                Some(path) if !save_as => path.clone(),
+                _ => {
+                    let mut chooser = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseSaveFile);
+                    chooser.set_option(dialog::NativeFileChooserOptions::SaveAsConfirm);
+                    chooser.show();
+                    let path = chooser.filename();
+                    if path.as_os_str().is_empty() {
+                        return false;
+                    }
+                    path
+                }
+            };
+
+            match fs::write(&path, buf.text()) {
+                Ok(()) => {
+                    self.path = Some(path);
+                    self.set_dirty(false);
+                    true
+                }
+                Err(err) => {
+                    dialog::alert_default(&format!("Could not save {}\n{}", path.display(), err));
+                    false
+                }
+            }
+        }
+
+        /// Asks what to do with unsaved changes before they would be lost.
+        /// Returns true if it is all right to go on.
+        fn confirm_discard(&mut self, buf: &TextBuffer) -> bool {
+            if !self.dirty {
+                return true;
+            }
+            match dialog::choice2_default("There are unsaved changes.  Save them first?",
+                                          "Save", "Don't save", "Cancel") {
+                Some(0) => self.save(buf, false),
+                Some(1) => true,
+                _ => false,
+            }
+        }
+
+        /// Asks for a file and reads it into the buffer.
+        fn open(&mut self, buf: &mut TextBuffer) {
+            if !self.confirm_discard(buf) {
+                return;
+            }
+            let mut chooser = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+            chooser.show();
+            let path = chooser.filename();
+            if !path.as_os_str().is_empty() {
+                self.load(buf, &path);
+            }
+        }
+
+        /// Throws away the changes since the last save, or since the editor opened.
+        fn revert(&mut self, buf: &mut TextBuffer) {
+            if !self.dirty {
+                return;
+            }
+            if dialog::choice2_default("Throw away all unsaved changes?", "Revert", "Cancel", "") != Some(0) {
+                return;
+            }
+            match self.path.clone() {
+                Some(path) => {
+                    self.load(buf, &path);
+                }
+                None => {
+                    buf.set_text(&self.start);
+                    self.history.clear();
+                    self.set_dirty(false);
+                }
+            }
+        }
+    }
+
+    /// Creates a simple editor set up from `opts`.  File/Open, Save,
+    /// Save As and Revert use the native file chooser.  A `*` in the title
+    /// shows there are unsaved changes, and closing the window or choosing
+    /// File/Finished asks whether to save them.  If `opts.path` is set the
+    /// editor opens that file.  Edit/Undo and Redo (Ctrl+Z, Ctrl+Y) step
+    /// through an `EditHistory`, and Edit/Find (Ctrl+F) opens a find &
+    /// replace bar, a `FindBar`, under the text.  `opts.highlight` turns on
+    /// syntax highlighting through a `Highlighter`.  `opts.line_numbers`
+    /// adds a line-number gutter and `opts.status_bar` a status bar with
+    /// the line, column, selection length and word count.  Edit/Go to line
+    /// (Ctrl+G) jumps to a line.
+    /// Returns the final contents of the editor.
+    ///
+    /// Example:
+    ///
+    ///     use lib_myfltk::fltkutils::*;
+    ///
+    ///     let opts = EditorOptions::default()
+    ///         .with_title("Question editor")
+    ///         .with_path("questions.txt");
+    ///
+    ///     let text = fltk_simple_editor_with(&opts);
+    ///
+    ///     println!("\n The editor held {} characters. \n", text.len());
+    ///
+    pub fn fltk_simple_editor_with(opts: &EditorOptions) -> String {
+        let _edtr = App::default();
+        let mut buf = TextBuffer::default();
+        let mut win = Window::default().with_size(800, 400);
+        set_font_size(20);
+        win.set_color(Color::Yellow);
+        win.make_resizable(true);
+
+        // region Lay out the menubar over the editor.
+        let mut column = group::Flex::default_fill().column();
+        let mut menubar = menu::MenuBar::default();
+        column.fixed(&menubar, 40);
+
+        let mut simped = TextEditor::default();
+        simped.set_buffer(buf.clone());   // Clone is used here to avoid an ownership error.
+        simped.wrap_mode(text::WrapMode::AtBounds, 0);
+        simped.set_color(Color::White);
+        simped.set_text_size(22);
+        simped.set_text_color(Color::Black);
+        if opts.line_numbers {
+            simped.set_linenumber_width(60);
+            simped.set_linenumber_size(16);
+            simped.set_linenumber_fgcolor(Color::from_rgb(110, 110, 110));
+        }
+
+        let mut findbar = FindBar::new(0, 0, 0, 0, &simped);
+        column.fixed(&*findbar, 70);
+        findbar.hide();   // Edit/Find shows it.
+        if let Some(rules) = &opts.highlight {
+            let highlighter = Highlighter::attach(&simped, rules.clone());
+            findbar.set_highlighter(&highlighter);
+        }
+
+        let mut statusbar = frame::Frame::default();
+        column.fixed(&statusbar, 28);
+        statusbar.set_frame(FrameType::ThinDownBox);
+        statusbar.set_align(Align::Left | Align::Inside);
+        statusbar.set_label_size(14);
+        if !opts.status_bar {
+            statusbar.hide();
+        }
+
+        column.end();
+        win.end();
+        // endregion
+
+        // region Load the starting text & keep track of unsaved changes.
+        let history = EditHistory::new(&buf);
+        let file = Rc::new(RefCell::new(EditorFile {
+            win: win.clone(),
+            title: opts.title.clone(),
+            path: None,
+            start: opts.text.clone(),
+            dirty: false,
+            history: history.clone(),
+        }));
+        buf.set_text(&opts.text);
+        match &opts.path {
+            Some(path) if path.exists() => {
+                file.borrow_mut().load(&mut buf, path);
+            }
+            Some(path) => file.borrow_mut().path = Some(path.clone()),  // A new file, written on the first save.
+            None => {}
+        }
+        file.borrow_mut().set_dirty(false);
+        history.clear();
+
+        // Loading text inside an EditorFile method already holds the borrow, so skip those changes.
+        let file_mod = file.clone();
+        buf.add_modify_callback(move |_, inserted, deleted, _, _| {
+            if inserted > 0 || deleted > 0 {
+                if let Ok(mut file) = file_mod.try_borrow_mut() {
+                    if !file.dirty {
+                        file.set_dirty(true);
+                    }
+                }
+            }
+        });
+        // endregion
+
+        // region Fill in the File menu.
+        let file_open = file.clone();
+        let mut buf_open = buf.clone();
+        menubar.add("File/Open...\t", Shortcut::Ctrl | 'o', menu::MenuFlag::Normal,
+                    move |_| file_open.borrow_mut().open(&mut buf_open));
+
+        let file_save = file.clone();
+        let buf_save = buf.clone();
+        menubar.add("File/Save\t", Shortcut::Ctrl | 's', menu::MenuFlag::Normal,
+                    move |_| { file_save.borrow_mut().save(&buf_save, false); });
+
+        let file_saveas = file.clone();
+        let buf_saveas = buf.clone();
+        menubar.add("File/Save As...\t", Shortcut::Ctrl | Shortcut::Shift | 's', menu::MenuFlag::Normal,
+                    move |_| { file_saveas.borrow_mut().save(&buf_saveas, true); });
+
+        let file_revert = file.clone();
+        let mut buf_revert = buf.clone();
+        menubar.add("File/Revert\t", Shortcut::None, menu::MenuFlag::MenuDivider,
+                    move |_| file_revert.borrow_mut().revert(&mut buf_revert));
+
+        // endregion
+
+        // region Fill in the Edit menu.
+        let history_undo = history.clone();
+        let mut simped_undo = simped.clone();
+        menubar.add("Edit/Undo\t", Shortcut::Ctrl | 'z', menu::MenuFlag::Normal,
+                    move |_| editor_undo(&mut simped_undo, &history_undo, false));
+        let history_redo = history.clone();
+        let mut simped_redo = simped.clone();
+        menubar.add("Edit/Redo\t", Shortcut::Ctrl | 'y', menu::MenuFlag::MenuDivider,
+                    move |_| editor_undo(&mut simped_redo, &history_redo, true));
+
+        // The editor has its own one-level undo on Ctrl+Z, so catch the keys before it does.
+        let history_keys = history.clone();
+        simped.handle(move |ed, ev| {
+            if ev != Event::KeyDown || !app::is_event_ctrl() {
+                return false;
+            }
+            let key = app::event_key();
+            if key == Key::from_char('z') && !app::is_event_shift() {
+                editor_undo(ed, &history_keys, false);
+                true
+            } else if key == Key::from_char('y') || key == Key::from_char('z') {
+                editor_undo(ed, &history_keys, true);
+                true
+            } else {
+                false
+            }
+        });
+
+        let mut findbar_find = findbar.clone();
+        menubar.add("Edit/Find...\t", Shortcut::Ctrl | 'f', menu::MenuFlag::Normal,
+                    move |_| findbar_find.focus());
+        let mut findbar_replace = findbar.clone();
+        menubar.add("Edit/Replace...\t", Shortcut::Ctrl | 'h', menu::MenuFlag::Normal,
+                    move |_| findbar_replace.focus());
+        let mut findbar_next = findbar.clone();
+        menubar.add("Edit/Find next\t", Shortcut::None | Key::F3, menu::MenuFlag::Normal,
+                    move |_| findbar_next.find_next());
+        let mut findbar_previous = findbar.clone();
+        menubar.add("Edit/Find previous\t", Shortcut::Shift | Key::F3, menu::MenuFlag::MenuDivider,
+                    move |_| findbar_previous.find_previous());
+        let mut simped_goto = simped.clone();
+        menubar.add("Edit/Go to line...\t", Shortcut::Ctrl | 'g', menu::MenuFlag::Normal,
+                    move |_| editor_go_to_line(&mut simped_goto));
+        // endregion
+
+        // region Keep the status bar up to date.
+        // There's no callback for the cursor moving, so check on it a few times a second.
+        // The words are only counted again on a check after the text has changed.
+        if opts.status_bar {
+            let recount = Rc::new(Cell::new(true));
+            let recount_mod = recount.clone();
+            buf.add_modify_callback(move |_, inserted, deleted, _, _| {
+                if inserted > 0 || deleted > 0 {
+                    recount_mod.set(true);
+                }
+            });
+
+            let simped_status = simped.clone();
+            let win_status = win.clone();
+            let buf_status = buf.clone();
+            let mut words = 0;
+            let mut last = String::new();
+            app::add_timeout3(0.0, move |handle| {
+                if !win_status.shown() {
+                    return;   // The editor has closed.
+                }
+                if recount.replace(false) {
+                    words = buf_status.text().split_whitespace().count();
+                }
+                let status = editor_status(&simped_status, words);
+                if status != last {
+                    statusbar.set_label(&status);
+                    last = status;
+                }
+                app::repeat_timeout3(0.2, handle);
+            });
+        }
+        // endregion
+
+        // region Close the editor.
+        // Finished & the window's close button both check for unsaved changes first.
+        let file_quit = file.clone();
+        let buf_quit = buf.clone();
+        let try_close = Rc::new(move || {
+            let mut file = file_quit.borrow_mut();
+            if file.confirm_discard(&buf_quit) {
+                file.win.hide();
+            }
+        });
+        let try_close_menu = try_close.clone();
+        let quit_idx = menubar.add("File/Finished\t", Shortcut::Ctrl | 'q', menu::MenuFlag::Normal,
+                                   move |_| try_close_menu());
+        menubar.at(quit_idx).unwrap().set_label_color(Color::Red);
+
+        win.set_callback(move |_| {
+            if app::event() == Event::Close {   // Ignore Escape, which is easy to hit by mistake.
+                try_close();
+            }
+        });
+        // endregion
+
+        win.show();
+        while win.shown() {
+            app::wait();
+        }
+
+        buf.text()
+    }
+
+    /// Creates a menubar to be used with the `fltk_simple_editor()`.
+    /// The new menubar only has ine entry -- `File/Quit` -- that provides
+    /// a pattern that can be used to create more menu items
+    pub fn fltk_simple_editor_menubar() -> menu::MenuBar {
+
+        let mut menubar = menu::MenuBar::new(0, 0, 800, 40, "");
+
+        let quit_idx = menubar.add(
+            "File/Finished\t",
+            Shortcut::None,
+            menu::MenuFlag::Normal,
+            |_| {
+                quit();
+            },
+        );
+        menubar.at(quit_idx).unwrap().set_label_color(Color::Red);
+
+        menubar
+    }
+
+    /// Replaces highlighted text in a `TextEditor` with the text
+    /// passed in the `rpltxt` parameter.  The swap is a single change to
+    /// the buffer, so an `EditHistory` undoes it in one step.
+    pub fn fltk_replace_highlighted_text(edtr: &TextEditor, buf: &mut TextBuffer, rpltxt: &str) {
+        let (x, y) = match edtr.buffer().unwrap().selection_position() {
+            Some(position) => position,
+            None => panic!("\nError!  Could not find a cursor position in the editor.\n"),
+        };
+
+        buf.replace(x, y, rpltxt);                // Swap the selected text for the new text and
+        edtr.buffer().unwrap().unselect();        // Unhighlight text
+    }
+
+    /// One change to a buffer:  `deleted` was replaced by `inserted` at byte `pos`.
+    #[derive(Debug, Clone)]
+    struct BufferChange {
+        pos: usize,
+        inserted: String,
+        deleted: String,
+    }
+
+    /// What the last undo step was made of, so typing can be merged into it.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum StepKind {
+        Typing,
+        Erasing,
+        Other,
+    }
+
+    /// The undo & redo stacks behind an `EditHistory`.
+    struct HistoryState {
+        undo: VecDeque<Vec<BufferChange>>,
+        redo: Vec<Vec<BufferChange>>,
+        last_kind: StepKind,
+        depth: usize,            // How many transactions are open.
+        replaying: bool,         // True while undo or redo is changing the buffer.
+        limit: usize,
+    }
+
+    impl HistoryState {
+        /// Adds a change from the buffer, either to the open transaction,
+        /// to the last step if it continues the same run of typing or
+        /// erasing, or as a new step.
+        fn record(&mut self, change: BufferChange) {
+            self.redo.clear();
+
+            if self.depth > 0 {
+                if let Some(step) = self.undo.back_mut() {
+                    step.push(change);
+                }
+                return;
+            }
+
+            let kind = match (change.inserted.chars().count(), change.deleted.chars().count()) {
+                (1, 0) => StepKind::Typing,
+                (0, 1) => StepKind::Erasing,
+                _ => StepKind::Other,
+            };
+            if kind != StepKind::Other && kind == self.last_kind {
+                if let Some(last) = self.undo.back_mut().and_then(|step| step.last_mut()) {
+                    if kind == StepKind::Typing && change.pos == last.pos + last.inserted.len() {
+                        last.inserted.push_str(&change.inserted);
+                        self.end_typing_run(&change);
+                        return;
+                    }
+                    if kind == StepKind::Erasing && change.pos + change.deleted.len() == last.pos {
+                        last.deleted.insert_str(0, &change.deleted);   // Backspace.
+                        last.pos = change.pos;
+                        return;
+                    }
+                    if kind == StepKind::Erasing && change.pos == last.pos {
+                        last.deleted.push_str(&change.deleted);        // Delete.
+                        return;
+                    }
+                }
+            }
+
+            self.last_kind = kind;
+            self.undo.push_back(vec![change.clone()]);
+            self.end_typing_run(&change);
+            if self.undo.len() > self.limit {
+                self.undo.pop_front();
+            }
+        }
+
+        /// Typing is undone a word at a time, so a space or new line ends the run.
+        fn end_typing_run(&mut self, change: &BufferChange) {
+            if !change.inserted.is_empty() && change.inserted.chars().all(char::is_whitespace) {
+                self.last_kind = StepKind::Other;
+            }
+        }
+    }
+
+    /// A multi-level undo & redo history for a `TextBuffer`.  Every change
+    /// to the buffer is recorded, whether it is typed or made by code.
+    /// Typing and erasing are grouped a word at a time, and changes made
+    /// between `begin()` and `end()` -- or inside `transaction()` -- are
+    /// undone in one step.
+    ///
+    /// Example:
+    ///
+    ///     use fltk::text::TextBuffer;
+    ///     use lib_myfltk::fltkutils::EditHistory;
+    ///
+    ///     let mut buf = TextBuffer::default();
+    ///     buf.set_text("The cat sat.");
+    ///     let history = EditHistory::new(&buf);
+    ///
+    ///     history.transaction(|buf| {
+    ///         buf.replace(4, 7, "dog");
+    ///         buf.append("  It was tired.");
+    ///     });
+    ///     assert_eq!(buf.text(), "The dog sat.  It was tired.");
+    ///
+    ///     history.undo();
+    ///     assert_eq!(buf.text(), "The cat sat.");
+    ///
+    #[derive(Clone)]
+    pub struct EditHistory {
+        buf: TextBuffer,
+        state: Rc<RefCell<HistoryState>>,
+    }
+
+    impl EditHistory {
+        /// Starts recording the changes made to `buf`.  The history holds
+        /// up to 500 steps; see `set_limit()`.
+        pub fn new(buf: &TextBuffer) -> Self {
+            let state = Rc::new(RefCell::new(HistoryState {
+                undo: VecDeque::new(),
+                redo: Vec::new(),
+                last_kind: StepKind::Other,
+                depth: 0,
+                replaying: false,
+                limit: 500,
+            }));
+
+            let state_mod = state.clone();
+            let buf_mod = buf.clone();
+            let mut buf = buf.clone();
+            buf.add_modify_callback(move |pos, inserted, _deleted, _, deleted_text| {
+                let mut st = state_mod.borrow_mut();
+                if st.replaying || (inserted == 0 && deleted_text.is_empty()) {
+                    return;
+                }
+                let inserted = match inserted {
+                    0 => String::new(),
+                    n => buf_mod.text_range(pos, pos + n).unwrap_or_default(),
+                };
+                st.record(BufferChange { pos: pos as usize, inserted, deleted: deleted_text.to_string() });
+            });
+
+            EditHistory { buf, state }
+        }
+
+        /// Sets how many steps are kept.  The oldest are dropped first.
+        pub fn set_limit(&self, limit: usize) {
+            let mut st = self.state.borrow_mut();
+            st.limit = limit.max(1);
+            while st.undo.len() > st.limit {
+                st.undo.pop_front();
+            }
+        }
+
+        /// Starts a transaction.  Changes up to the matching `end()` are
+        /// undone as one step.  Transactions can be nested; only the
+        /// outermost one makes a step.
+        pub fn begin(&self) {
+            let mut st = self.state.borrow_mut();
+            if st.depth == 0 {
+                st.undo.push_back(Vec::new());
+                st.last_kind = StepKind::Other;
+            }
+            st.depth += 1;
+        }
+
+        /// Ends a transaction started with `begin()`.
+        pub fn end(&self) {
+            let mut st = self.state.borrow_mut();
+            if st.depth == 0 {
+                return;
+            }
+            st.depth -= 1;
+            if st.depth == 0 {
+                if st.undo.back().is_some_and(|step| step.is_empty()) {
+                    st.undo.pop_back();   // Nothing changed.
+                } else if st.undo.len() > st.limit {
+                    st.undo.pop_front();
+                }
+            }
+        }
+
+        /// Runs `edit` on the buffer as one transaction.
+        pub fn transaction<F: FnOnce(&mut TextBuffer)>(&self, edit: F) {
+            self.begin();
+            let mut buf = self.buf.clone();
+            edit(&mut buf);
+            self.end();
+        }
+
+        /// Undoes the last step.  Returns where the cursor should go, or
+        /// `None` if there was nothing to undo.
+        pub fn undo(&self) -> Option<usize> {
+            let step = {
+                let mut st = self.state.borrow_mut();
+                if st.depth > 0 {
+                    return None;
+                }
+                let step = st.undo.pop_back()?;
+                st.replaying = true;
+                st.last_kind = StepKind::Other;
+                step
+            };
+
+            let mut buf = self.buf.clone();
+            for change in step.iter().rev() {
+                let end = change.pos + change.inserted.len();
+                buf.replace(change.pos as i32, end as i32, &change.deleted);
+            }
+
+            let mut st = self.state.borrow_mut();
+            st.replaying = false;
+            let cursor = step.first().map(|change| change.pos + change.deleted.len());
+            st.redo.push(step);
+            cursor
+        }
+
+        /// Redoes the last step that was undone.  Returns where the cursor
+        /// should go, or `None` if there was nothing to redo.
+        pub fn redo(&self) -> Option<usize> {
+            let step = {
+                let mut st = self.state.borrow_mut();
+                if st.depth > 0 {
+                    return None;
+                }
+                let step = st.redo.pop()?;
+                st.replaying = true;
+                st.last_kind = StepKind::Other;
+                step
+            };
+
+            let mut buf = self.buf.clone();
+            for change in step.iter() {
+                let end = change.pos + change.deleted.len();
+                buf.replace(change.pos as i32, end as i32, &change.inserted);
+            }
+
+            let mut st = self.state.borrow_mut();
+            st.replaying = false;
+            let cursor = step.last().map(|change| change.pos + change.inserted.len());
+            st.undo.push_back(step);
+            cursor
+        }
+
+        /// Returns true if there is a step to undo.
+        pub fn can_undo(&self) -> bool {
+            !self.state.borrow().undo.is_empty()
+        }
+
+        /// Returns true if there is a step to redo.
+        pub fn can_redo(&self) -> bool {
+            !self.state.borrow().redo.is_empty()
+        }
+
+        /// Forgets every step, e.g. after a file is loaded.
+        pub fn clear(&self) {
+            let mut st = self.state.borrow_mut();
+            st.undo.clear();
+            st.redo.clear();
+            st.last_kind = StepKind::Other;
+        }
+    }
+
+    /// Undoes, or redoes, the last step of `history` in `edtr` and moves
+    /// the cursor to where the change was.
+    fn editor_undo(edtr: &mut TextEditor, history: &EditHistory, redo: bool) {
+        let cursor = if redo { history.redo() } else { history.undo() };
+        if let Some(pos) = cursor {
+            if let Some(mut buf) = edtr.buffer() {
+                buf.unselect();
+            }
+            edtr.set_insert_position(pos as i32);
+            edtr.show_insert_position();
+        }
+    }
+
+    /// The search settings, matches and widgets behind a `FindBar`.
+    struct FindState {
+        editor: TextEditor,
+        buf: TextBuffer,
+        style: TextBuffer,
+        count: frame::Frame,
+        query: String,
+        replacement: String,
+        case: bool,
+        word: bool,
+        regex: bool,
+        pattern: Option<Regex>,
+        bad_pattern: bool,
+        matches: Vec<(usize, usize)>,     // Byte ranges in the buffer.
+        highlighter: Option<Highlighter>,
+        edits: usize,                     // Counts edits, so a rescan waits for typing to pause.
+        rescan_pending: bool,
+    }
+
+    /// Builds the regex a `FindBar` searches with, or `None` for an empty query.
+    fn find_pattern(query: &str, case: bool, word: bool, regex: bool) -> Result<Option<Regex>, regex::Error> {
+        if query.is_empty() {
+            return Ok(None);
+        }
+        let mut pattern = if regex { query.to_string() } else { regex::escape(query) };
+        if word {
+            pattern = format!(r"\b(?:{})\b", pattern);
+        }
+        RegexBuilder::new(&pattern).case_insensitive(!case).multi_line(true).build().map(Some)
+    }
+
+    /// The byte ranges of every non-empty match of `re` in `text`.
+    fn find_matches(re: &Regex, text: &str) -> Vec<(usize, usize)> {
+        re.find_iter(text)
+            .filter(|m| m.start() < m.end())
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+
+    /// Moves byte ranges to follow an edit at `pos` that inserted and
+    /// deleted the given number of bytes.  Ranges the edit cut into are dropped.
+    fn shift_ranges(ranges: &mut Vec<(usize, usize)>, pos: usize, inserted: usize, deleted: usize) {
+        ranges.retain_mut(|range| {
+            if range.1 <= pos {
+                true
+            } else if range.0 >= pos + deleted {
+                *range = (range.0 + inserted - deleted, range.1 + inserted - deleted);
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    /// Returns the text that replaces the match starting at `start`.  With
+    /// `expand` set (Regex mode) the replacement can use `$1`, `${name}` and so on.
+    fn replacement_at(re: &Regex, expand: bool, replacement: &str, text: &str, start: usize) -> String {
+        if !expand {
+            return replacement.to_string();
+        }
+        let mut out = String::new();
+        if let Some(caps) = re.captures_at(text, start) {
+            caps.expand(replacement, &mut out);
+        }
+        out
+    }
+
+    /// Returns `text` with every match of `re` replaced, expanding `$1` and
+    /// so on only when `expand` is set.
+    fn replace_matches<'t>(re: &Regex, expand: bool, replacement: &str, text: &'t str) -> std::borrow::Cow<'t, str> {
+        if expand {
+            re.replace_all(text, replacement)
+        } else {
+            re.replace_all(text, NoExpand(replacement))
+        }
+    }
+
+    impl FindState {
+        /// How long typing must pause before the matches are found again.
+        const RESCAN_DELAY: f64 = 0.15;
+
+        /// Builds the pattern from the query and the Match case, Whole word
+        /// and Regex settings, then finds the matches again.
+        fn compile(&mut self) {
+            match find_pattern(&self.query, self.case, self.word, self.regex) {
+                Ok(pattern) => {
+                    self.pattern = pattern;
+                    self.bad_pattern = false;
+                }
+                Err(_) => {
+                    self.pattern = None;
+                    self.bad_pattern = true;
+                }
+            }
+            self.rescan();
+        }
+
+        /// Finds every match in the buffer and highlights them through the style buffer.
+        fn rescan(&mut self) {
+            let text = self.buf.text();
+            self.matches = match &self.pattern {
+                Some(re) => find_matches(re, &text),
+                None => Vec::new(),
+            };
+
+            match &self.highlighter {
+                Some(highlighter) => highlighter.set_found(&self.matches),
+                None => {
+                    let mut styles = vec![b'A'; text.len()];
+                    for &(start, end) in &self.matches {
+                        styles[start..end].fill(b'B');
+                    }
+                    self.style.set_text(&String::from_utf8(styles).unwrap_or_default());
+                }
+            }
+            self.show_count();
+            self.editor.redraw();
+        }
+
+        /// Keeps the matches lined up with the text after an edit, until the
+        /// rescan that `rescan_later()` schedules finds them again.
+        fn edited(&mut self, pos: usize, inserted: usize, deleted: usize) {
+            self.edits += 1;
+            shift_ranges(&mut self.matches, pos, inserted, deleted);
+            if self.highlighter.is_none() {
+                let end = (pos + deleted).min(self.style.length() as usize);
+                self.style.replace(pos as i32, end as i32, &"A".repeat(inserted));
+            }
+            self.show_count();
+        }
+
+        /// Finds the matches again once there have been no edits for
+        /// `RESCAN_DELAY` seconds, rather than searching the whole text on
+        /// every keystroke.  `seen` is the edit count when it is called.
+        fn rescan_later(state: &Rc<RefCell<FindState>>, mut seen: usize) {
+            let state = state.clone();
+            app::add_timeout3(FindState::RESCAN_DELAY, move |handle| match state.try_borrow_mut() {
+                Ok(mut st) if st.edits == seen => {
+                    st.rescan_pending = false;
+                    st.rescan();
+                }
+                Ok(st) => {
+                    seen = st.edits;
+                    app::repeat_timeout3(FindState::RESCAN_DELAY, handle);
+                }
+                Err(_) => app::repeat_timeout3(FindState::RESCAN_DELAY, handle),
+            });
+        }
+
+        /// Returns the match that is selected in the editor, if any.
+        fn current_match(&self) -> Option<usize> {
+            let (start, end) = self.buf.selection_position()?;
+            self.matches.iter().position(|&(s, e)| s as i32 == start && e as i32 == end)
+        }
+
+        /// Shows "2 of 7", "7 matches", "No matches" or "Bad pattern".
+        fn show_count(&mut self) {
+            let label = if self.bad_pattern {
+                "Bad pattern".to_string()
+            } else if self.query.is_empty() {
+                String::new()
+            } else if self.matches.is_empty() {
+                "No matches".to_string()
+            } else if let Some(idx) = self.current_match() {
+                format!("{} of {}", idx + 1, self.matches.len())
+            } else {
+                format!("{} matches", self.matches.len())
+            };
+            self.count.set_label(&label);
+        }
+
+        /// Selects match `idx` and scrolls it into view.
+        fn select_match(&mut self, idx: usize) {
+            let (start, end) = self.matches[idx];
+            self.buf.select(start as i32, end as i32);
+            self.editor.set_insert_position(end as i32);
+            self.editor.show_insert_position();
+            self.show_count();
+        }
+
+        /// Selects the first match after the cursor, wrapping to the top.
+        fn find_next(&mut self) {
+            if self.matches.is_empty() {
+                return;
+            }
+            let pos = self.editor.insert_position() as usize;
+            let idx = self.matches.iter().position(|&(start, _)| start >= pos).unwrap_or(0);
+            self.select_match(idx);
+        }
+
+        /// Selects the last match before the selection or cursor, wrapping to the bottom.
+        fn find_previous(&mut self) {
+            if self.matches.is_empty() {
+                return;
+            }
+            let pos = match self.buf.selection_position() {
+                Some((start, _)) => start as usize,
+                None => self.editor.insert_position() as usize,
+            };
+            let idx = self.matches.iter().rposition(|&(start, _)| start < pos)
+                .unwrap_or(self.matches.len() - 1);
+            self.select_match(idx);
+        }
+
+        /// Replaces the selected match, then selects the next one.  If no
+        /// match is selected, just selects the next one.
+        fn replace_one(&mut self) {
+            if let Some(idx) = self.current_match() {
+                let (start, end) = self.matches[idx];
+                let with = match &self.pattern {
+                    Some(re) => replacement_at(re, self.regex, &self.replacement, &self.buf.text(), start),
+                    None => self.replacement.clone(),
+                };
+                self.buf.replace(start as i32, end as i32, &with);
+                self.editor.set_insert_position((start + with.len()) as i32);
+                self.rescan();
+            }
+            self.find_next();
+        }
+
+        /// Replaces every match as a single change to the buffer.
+        fn replace_all(&mut self) {
+            let Some(re) = self.pattern.clone() else { return };
+            let count = self.matches.len();
+            if count == 0 {
+                return;
+            }
+            let text = self.buf.text();
+            let changed = replace_matches(&re, self.regex, &self.replacement, &text);
+            self.buf.replace(0, text.len() as i32, &changed);
+            self.rescan();
+            self.count.set_label(&format!("Replaced {}", count));
+        }
+    }
+
+    /// A find & replace bar for a `TextEditor`.  It has Find and Replace
+    /// fields, previous & next buttons, Replace and Replace all, and Match
+    /// case, Whole word and Regex settings.  Every match is highlighted
+    /// through a style buffer and the bar shows how many there are.
+    /// In the Find field Enter finds the next match, Shift+Enter the
+    /// previous one, and Escape hides the bar.
+    ///
+    /// Example:
+    ///
+    ///     use fltk::{prelude::*, *};
+    ///     use lib_myfltk::fltkutils::FindBar;
+    ///
+    ///     let app = app::App::default();
+    ///     let mut win = window::Window::default().with_size(800, 400);
+    ///
+    ///     let mut buf = text::TextBuffer::default();
+    ///     buf.set_text("The cat sat on the mat.");
+    ///     let mut edtr = text::TextEditor::new(0, 0, 800, 330, None);
+    ///     edtr.set_buffer(buf);
+    ///     let _findbar = FindBar::new(0, 330, 800, 70, &edtr);
+    ///
+    ///     win.end();
+    ///     win.show();
+    ///     app.run().unwrap();
+    ///
+    #[derive(Clone)]
+    pub struct FindBar {
+        inner: group::Flex,
+        find: input::Input,
+        state: Rc<RefCell<FindState>>,
+    }
+
+    fltk::widget_extends!(FindBar, group::Flex, inner);
+
+    impl FindBar {
+        /// Creates the bar and hooks it up to `editor`.  The bar takes over
+        /// the editor's highlight data for marking the matches, replacing
+        /// any style buffer set before.  To keep syntax highlighting, call
+        /// `set_highlighter()` after this so the matches are marked through
+        /// the `Highlighter` instead.
+        pub fn new(x: i32, y: i32, w: i32, h: i32, editor: &TextEditor) -> Self {
+            let mut editor = editor.clone();
+            let buf = match editor.buffer() {
+                Some(buf) => buf,
+                None => {
+                    let buf = TextBuffer::default();
+                    editor.set_buffer(buf.clone());
+                    buf
+                }
+            };
+
+            // region Lay out the Find row over the Replace row.
+            let mut inner = group::Flex::new(x, y, w, h, None).column();
+            inner.set_margin(4);
+
+            let mut findrow = group::Flex::default().row();
+            let findlabel = frame::Frame::default().with_label("Find:");
+            findrow.fixed(&findlabel, 60);
+            let mut find = input::Input::default();
+            let mut previous = Button::default().with_label("@<");
+            previous.set_tooltip("Find previous (Shift+Enter)");
+            findrow.fixed(&previous, 30);
+            let mut next = Button::default().with_label("@>");
+            next.set_tooltip("Find next (Enter)");
+            findrow.fixed(&next, 30);
+            let mut case = button::CheckButton::default().with_label("Match case");
+            findrow.fixed(&case, 105);
+            let mut word = button::CheckButton::default().with_label("Whole word");
+            findrow.fixed(&word, 105);
+            let mut regexmode = button::CheckButton::default().with_label("Regex");
+            findrow.fixed(&regexmode, 70);
+            let count = frame::Frame::default();
+            findrow.fixed(&count, 110);
+            findrow.end();
+
+            let mut replacerow = group::Flex::default().row();
+            let replacelabel = frame::Frame::default().with_label("Replace:");
+            replacerow.fixed(&replacelabel, 60);
+            let mut replace = input::Input::default();
+            let mut replaceone = Button::default().with_label("Replace");
+            replacerow.fixed(&replaceone, 80);
+            let mut replaceall = Button::default().with_label("Replace all");
+            replacerow.fixed(&replaceall, 100);
+            replacerow.end();
+
+            inner.end();
+            // endregion
+
+            // region Give the editor a style buffer:  'A' is plain text, 'B' is a match.
+            let style = TextBuffer::default();
+            let plain = text::StyleTableEntry {
+                color: editor.text_color(),
+                font: editor.text_font(),
+                size: editor.text_size(),
+            };
+            let found = text::StyleTableEntry {
+                color: Color::from_rgb(200, 0, 0),
+                font: editor.text_font(),
+                size: editor.text_size(),
+            };
+            editor.set_highlight_data(style.clone(), vec![plain, found]);
+            // endregion
+
+            let state = Rc::new(RefCell::new(FindState {
+                editor,
+                buf: buf.clone(),
+                style,
+                count,
+                query: String::new(),
+                replacement: String::new(),
+                case: false,
+                word: false,
+                regex: false,
+                pattern: None,
+                bad_pattern: false,
+                matches: Vec::new(),
+                highlighter: None,
+                edits: 0,
+                rescan_pending: false,
+            }));
+            state.borrow_mut().rescan();
+
+            // region Keep the matches up to date as the text & the settings change.
+            // Changes made by FindState itself already hold the borrow and rescan on their own.
+            let state_mod = state.clone();
+            let mut buf = buf;
+            buf.add_modify_callback(move |pos, inserted, deleted, _, _| {
+                if inserted > 0 || deleted > 0 {
+                    if let Ok(mut st) = state_mod.try_borrow_mut() {
+                        st.edited(pos as usize, inserted as usize, deleted as usize);
+                        if !st.rescan_pending {
+                            st.rescan_pending = true;
+                            FindState::rescan_later(&state_mod, st.edits);
+                        }
+                    }
+                }
+            });
+
+            let state_find = state.clone();
+            find.set_trigger(CallbackTrigger::Changed);
+            find.set_callback(move |f| {
+                let mut st = state_find.borrow_mut();
+                st.query = f.value();
+                st.compile();
+            });
+
+            let state_replace = state.clone();
+            replace.set_trigger(CallbackTrigger::Changed);
+            replace.set_callback(move |r| state_replace.borrow_mut().replacement = r.value());
+
+            let state_case = state.clone();
+            case.set_callback(move |c| {
+                let mut st = state_case.borrow_mut();
+                st.case = c.is_checked();
+                st.compile();
+            });
+            let state_word = state.clone();
+            word.set_callback(move |c| {
+                let mut st = state_word.borrow_mut();
+                st.word = c.is_checked();
+                st.compile();
+            });
+            let state_regex = state.clone();
+            regexmode.set_callback(move |c| {
+                let mut st = state_regex.borrow_mut();
+                st.regex = c.is_checked();
+                st.compile();
+            });
+            // endregion
+
+            // region Do the buttons & the Find field's keys.
+            let state_btn = state.clone();
+            previous.set_callback(move |_| state_btn.borrow_mut().find_previous());
+            let state_btn = state.clone();
+            next.set_callback(move |_| state_btn.borrow_mut().find_next());
+            let state_btn = state.clone();
+            replaceone.set_callback(move |_| state_btn.borrow_mut().replace_one());
+            let state_btn = state.clone();
+            replaceall.set_callback(move |_| state_btn.borrow_mut().replace_all());
+
+            let state_keys = state.clone();
+            let mut bar_keys = inner.clone();
+            find.handle(move |_, ev| {
+                if ev != Event::KeyDown {
+                    return false;
+                }
+                match app::event_key() {
+                    Key::Enter | Key::KPEnter => {
+                        let mut st = state_keys.borrow_mut();
+                        if app::is_event_shift() {
+                            st.find_previous();
+                        } else {
+                            st.find_next();
+                        }
+                        true
+                    }
+                    Key::Escape => {
+                        bar_keys.hide();
+                        relayout_parent(&bar_keys);
+                        let _ = state_keys.borrow_mut().editor.take_focus();
+                        true
+                    }
+                    _ => false,
+                }
+            });
+            // endregion
+
+            FindBar { inner, find, state }
+        }
+
+        /// Shows the bar and puts the cursor in the Find field, with the
+        /// editor's selection as the text to find if it is on one line.
+        pub fn focus(&mut self) {
+            self.inner.show();
+            relayout_parent(&self.inner);
+            let selected = self.state.borrow().buf.selection_text();
+            if !selected.is_empty() && !selected.contains('\n') {
+                self.find.set_value(&selected);
+                self.find.do_callback();
+            }
+            let _ = self.find.take_focus();
+            let len = self.find.value().len() as i32;
+            let _ = self.find.set_position(0);
+            let _ = self.find.set_mark(len);
+        }
+
+        /// Marks the matches through `highlighter` instead of the bar's own
+        /// style buffer, so they show on top of the syntax highlighting.
+        pub fn set_highlighter(&mut self, highlighter: &Highlighter) {
+            highlighter.install();
+            let mut st = self.state.borrow_mut();
+            st.highlighter = Some(highlighter.clone());
+            st.rescan();
+        }
+
+        /// Selects the next match after the cursor.
+        pub fn find_next(&mut self) {
+            self.state.borrow_mut().find_next();
+        }
+
+        /// Selects the match before the cursor.
+        pub fn find_previous(&mut self) {
+            self.state.borrow_mut().find_previous();
+        }
+
+        /// Replaces every match.
+        pub fn replace_all(&mut self) {
+            self.state.borrow_mut().replace_all();
+        }
+
+        /// Returns the number of matches.
+        pub fn match_count(&self) -> usize {
+            self.state.borrow().matches.len()
+        }
+    }
+
+    /// One rule of a `SyntaxRules` set:  what to look for on each line and
+    /// the style (an index into the style table) to paint it with.
+    #[derive(Clone)]
+    enum HighlightRule {
+        Pattern(Regex, usize),
+        Custom(RangeFinder, usize),
+    }
+
+    /// Finds the byte ranges to paint on one line, for `SyntaxRules::with_closure()`.
+    type RangeFinder = Rc<dyn Fn(&str) -> Vec<Range<usize>>>;
+
+    /// The ways adding a rule to a `SyntaxRules` set can fail.
+    ///
+    #[derive(Debug, Clone)]
+    pub enum SyntaxRulesError {
+        /// The pattern isn't a valid regex.
+        Pattern(regex::Error),
+        /// The set already has `SyntaxRules::MAX_STYLES` rules.
+        TooManyStyles,
+    }
+
+    impl fmt::Display for SyntaxRulesError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                SyntaxRulesError::Pattern(err) => write!(f, "bad highlight pattern: {}", err),
+                SyntaxRulesError::TooManyStyles => write!(f, "a highlight set can't have more than {} rules", SyntaxRules::MAX_STYLES),
+            }
+        }
+    }
+
+    impl std::error::Error for SyntaxRulesError {}
+
+    impl From<regex::Error> for SyntaxRulesError {
+        fn from(err: regex::Error) -> Self {
+            SyntaxRulesError::Pattern(err)
+        }
+    }
+
+    /// A set of highlighting rules for a `Highlighter`.  Each rule has its
+    /// own color, and optionally its own font.  Rules work a line at a time
+    /// and are applied in the order they were added, so a later rule paints
+    /// over an earlier one -- add comments last so they win.  A set holds
+    /// up to `SyntaxRules::MAX_STYLES` rules.
+    ///
+    /// Example:
+    ///
+    ///     use fltk::enums::Color;
+    ///     use lib_myfltk::fltkutils::{SyntaxRules, SyntaxRulesError};
+    ///
+    ///     fn main() -> Result<(), SyntaxRulesError> {
+    ///         let rules = SyntaxRules::default()
+    ///             .with_keywords(&["if", "else", "while"], Color::Blue, None)?
+    ///             .with_pattern(r"\d+", Color::Magenta, None)?
+    ///             .with_closure(|line| match line.find("//") {
+    ///                 Some(at) => vec![at..line.len()],
+    ///                 None => Vec::new(),
+    ///             }, Color::DarkGreen, None)?;
+    ///
+    ///         assert_eq!(rules.rule_count(), 3);
+    ///         Ok(())
+    ///     }
+    ///
+    #[derive(Clone, Default)]
+    pub struct SyntaxRules {
+        styles: Vec<(Color, Option<Font>)>,
+        rules: Vec<HighlightRule>,
+    }
+
+    impl fmt::Debug for SyntaxRules {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("SyntaxRules").field("rules", &self.rules.len()).finish()
+        }
+    }
+
+    impl SyntaxRules {
+        /// The most rules a set can hold.  Each style is stored as one
+        /// printable ASCII byte from 'A' up, and one more is kept for find matches.
+        pub const MAX_STYLES: usize = 60;
+
+        /// Adds a style to the table and returns its index.  Index 0 is plain text.
+        fn add_style(&mut self, color: Color, font: Option<Font>) -> Result<usize, SyntaxRulesError> {
+            if self.styles.len() >= SyntaxRules::MAX_STYLES {
+                return Err(SyntaxRulesError::TooManyStyles);
+            }
+            self.styles.push((color, font));
+            Ok(self.styles.len())
+        }
+
+        /// Paints whole-word matches of `words`.  A `font` of `None` uses the editor's font.
+        pub fn with_keywords(mut self, words: &[&str], color: Color, font: Option<Font>) -> Result<Self, SyntaxRulesError> {
+            let escaped: Vec<String> = words.iter().map(|word| regex::escape(word)).collect();
+            let re = Regex::new(&format!(r"\b(?:{})\b", escaped.join("|")))?;
+            let style = self.add_style(color, font)?;
+            self.rules.push(HighlightRule::Pattern(re, style));
+            Ok(self)
+        }
+
+        /// Paints matches of the regex `pattern`.  If the pattern has a
+        /// capture group, only the first group is painted.
+        pub fn with_pattern(mut self, pattern: &str, color: Color, font: Option<Font>) -> Result<Self, SyntaxRulesError> {
+            let re = Regex::new(pattern)?;
+            let style = self.add_style(color, font)?;
+            self.rules.push(HighlightRule::Pattern(re, style));
+            Ok(self)
+        }
+
+        /// Paints the byte ranges that `find` returns for each line.
+        pub fn with_closure<F>(mut self, find: F, color: Color, font: Option<Font>) -> Result<Self, SyntaxRulesError>
+            where F: Fn(&str) -> Vec<Range<usize>> + 'static
+        {
+            let style = self.add_style(color, font)?;
+            self.rules.push(HighlightRule::Custom(Rc::new(find), style));
+            Ok(self)
+        }
+
+        /// Returns the number of rules.
+        pub fn rule_count(&self) -> usize {
+            self.rules.len()
+        }
+
+        /// Adds one of the presets' patterns, which are known to be good.
+        fn with_known(self, pattern: &str, color: Color) -> Self {
+            self.with_pattern(pattern, color, None).unwrap()
+        }
+
+        /// Rules for Markdown:  headings, bold & italic text, inline code,
+        /// links, list markers and block quotes.  Fenced code blocks span
+        /// lines, so they aren't picked out.
+        pub fn markdown() -> Self {
+            SyntaxRules::default()
+                .with_known(r"^\s*([-*+]|\d+\.)\s", Color::Magenta)
+                .with_known(r"\*\*[^*]+\*\*|__[^_]+__", Color::DarkRed)
+                .with_known(r"(?:^|[^*])(\*[^*\s][^*]*\*)", Color::DarkYellow)
+                .with_known(r"\[[^\]]+\]\([^)]*\)", Color::DarkCyan)
+                .with_known(r"`[^`]+`", Color::DarkGreen)
+                .with_known(r"^>.*", Color::from_rgb(110, 110, 110))
+                .with_known(r"^#{1,6}\s.*", Color::Blue)
+        }
+
+        /// Rules for TOML:  table headers, keys, strings, numbers & booleans, and comments.
+        pub fn toml() -> Self {
+            SyntaxRules::default()
+                .with_known(r"\b(?:true|false|[-+]?\d[\d_]*(?:\.[\d_]+)?(?:[eE][-+]?\d+)?)\b", Color::Magenta)
+                .with_known(r#""(?:[^"\\]|\\.)*"|'[^']*'"#, Color::DarkGreen)
+                .with_known(r#"^\s*([A-Za-z0-9_.\-"]+)\s*="#, Color::DarkRed)
+                .with_known(r"^\s*\[\[?[^\]]+\]\]?", Color::Blue)
+                .with_known(r#"^(?:[^"'#]|"(?:[^"\\]|\\.)*"|'[^']*')*(#.*)$"#, Color::from_rgb(110, 110, 110))
+        }
+
+        /// Rules for JSON:  keys, strings, numbers, and `true`, `false` & `null`.
+        pub fn json() -> Self {
+            SyntaxRules::default()
+                .with_known(r"\b(?:true|false|null)\b|-?\b\d+(?:\.\d+)?(?:[eE][-+]?\d+)?\b", Color::Magenta)
+                .with_known(r#""(?:[^"\\]|\\.)*""#, Color::DarkGreen)
+                .with_known(r#"("(?:[^"\\]|\\.)*")\s*:"#, Color::DarkRed)
+        }
+
+        /// Rules for question templates:  `{var}` placeholders.
+        pub fn placeholders() -> Self {
+            SyntaxRules::default()
+                .with_known(r"\{[A-Za-z_][A-Za-z0-9_]*\}", Color::DarkBlue)
+        }
+
+        /// Returns the style byte for each byte of `line`.
+        fn style_line(&self, line: &str) -> Vec<u8> {
+            let mut styles = vec![b'A'; line.len()];
+            let mut paint = |range: Range<usize>, style: usize| {
+                let end = range.end.min(line.len());
+                if range.start < end {
+                    styles[range.start..end].fill(b'A' + style as u8);
+                }
+            };
+            for rule in &self.rules {
+                match rule {
+                    HighlightRule::Pattern(re, style) => {
+                        for caps in re.captures_iter(line) {
+                            if let Some(found) = caps.get(1).or_else(|| caps.get(0)) {
+                                paint(found.range(), *style);
+                            }
+                        }
+                    }
+                    HighlightRule::Custom(find, style) => {
+                        for range in find(line) {
+                            paint(range, *style);
+                        }
+                    }
+                }
+            }
+            styles
+        }
+    }
+
+    /// The style bytes behind a `Highlighter`, plus any find matches laid over them.
+    struct HighlightState {
+        rules: SyntaxRules,
+        buf: TextBuffer,
+        style: TextBuffer,
+        base: Vec<u8>,                   // The syntax style of each byte in the buffer.
+        found: Vec<(usize, usize)>,      // Ranges a `FindBar` wants marked.
+        found_style: u8,
+        entries: Vec<text::StyleTableEntry>,
+    }
+
+    impl HighlightState {
+        /// Restyles the whole lines between bytes `start` and `end`.
+        fn restyle(&mut self, start: usize, end: usize) {
+            let text = self.buf.text_range(start as i32, end as i32).unwrap_or_default();
+            let mut at = start;
+            for line in text.split('\n') {
+                let styles = self.rules.style_line(line);
+                self.base[at..at + styles.len()].copy_from_slice(&styles);
+                at += line.len() + 1;
+            }
+        }
+
+        /// Returns the style bytes from `start` to `end` with the find matches on top.
+        fn styled(&self, start: usize, end: usize) -> String {
+            let mut styles = self.base[start..end].to_vec();
+            for &(s, e) in &self.found {
+                let (s, e) = (s.max(start), e.min(end));
+                if s < e {
+                    styles[s - start..e - start].fill(self.found_style);
+                }
+            }
+            String::from_utf8(styles).unwrap_or_default()
+        }
+
+        /// Lines the styles and the find matches up with an edit of the text
+        /// at `pos`, then restyles the touched lines.
+        fn edited(&mut self, pos: usize, inserted: usize, deleted: usize) {
+            // Shift the old styles to line up with the new text.
+            let removed_end = (pos + deleted).min(self.base.len());
+            self.base.splice(pos..removed_end, std::iter::repeat_n(b'A', inserted));
+            self.style.replace(pos as i32, removed_end as i32, &"A".repeat(inserted));
+
+            let mut start = self.buf.line_start(pos as i32) as usize;
+            let mut end = self.buf.find_char_forward((pos + inserted) as i32, '\n').unwrap_or(self.buf.length()) as usize;
+            self.restyle(start, end);
+
+            // Matches after the edit move with the text.  Matches it cut into
+            // are dropped, and their marks are cleared along with the line.
+            let len = self.base.len();
+            self.found.retain_mut(|found| {
+                if found.1 <= pos {
+                    true
+                } else if found.0 >= removed_end {
+                    *found = ((found.0 + inserted).saturating_sub(deleted), (found.1 + inserted).saturating_sub(deleted));
+                    true
+                } else {
+                    start = start.min(found.0);
+                    end = end.max((found.1 + inserted).saturating_sub(deleted).min(len));
+                    false
+                }
+            });
+            let styled = self.styled(start, end);
+            self.style.replace(start as i32, end as i32, &styled);
+        }
+
+        /// Highlights the whole text again.
+        fn highlight_all(&mut self) {
+            let len = self.buf.length() as usize;
+            self.base = vec![b'A'; len];
+            self.restyle(0, len);
+            self.write_all();
+        }
+
+        /// Rewrites the whole style buffer.
+        fn write_all(&mut self) {
+            let styled = self.styled(0, self.base.len());
+            self.style.set_text(&styled);
+        }
+    }
+
+    /// Keeps a `TextEditor`'s style buffer in step with its text, using a
+    /// set of `SyntaxRules`.  When the text changes only the lines that
+    /// were touched are highlighted again.  A `FindBar` can mark its
+    /// matches on top of the highlighting; see `FindBar::set_highlighter()`.
+    ///
+    /// Example:
+    ///
+    ///     use fltk::{prelude::*, *};
+    ///     use lib_myfltk::fltkutils::{Highlighter, SyntaxRules};
+    ///
+    ///     let app = app::App::default();
+    ///     let mut win = window::Window::default().with_size(800, 400);
+    ///
+    ///     let mut buf = text::TextBuffer::default();
+    ///     buf.set_text("[package]\nname = \"demo\"  # The crate name\n");
+    ///     let mut edtr = text::TextEditor::new(0, 0, 800, 400, None);
+    ///     edtr.set_buffer(buf);
+    ///     let _highlighter = Highlighter::attach(&edtr, SyntaxRules::toml());
+    ///
+    ///     win.end();
+    ///     win.show();
+    ///     app.run().unwrap();
+    ///
+    #[derive(Clone)]
+    pub struct Highlighter {
+        editor: TextEditor,
+        state: Rc<RefCell<HighlightState>>,
+    }
+
+    impl Highlighter {
+        /// Highlights `editor`'s text with `rules` and keeps it highlighted.
+        /// Set the editor's text font, size and color first; plain text and
+        /// rules without a font of their own use them.
+        pub fn attach(editor: &TextEditor, rules: SyntaxRules) -> Self {
+            let mut editor = editor.clone();
+            let buf = match editor.buffer() {
+                Some(buf) => buf,
+                None => {
+                    let buf = TextBuffer::default();
+                    editor.set_buffer(buf.clone());
+                    buf
+                }
+            };
+
+            let entries = Highlighter::style_entries(&editor, &rules);
+            let found_style = b'A' + rules.styles.len() as u8 + 1;
+
+            let len = buf.length() as usize;
+            let state = Rc::new(RefCell::new(HighlightState {
+                rules,
+                buf: buf.clone(),
+                style: TextBuffer::default(),
+                base: vec![b'A'; len],
+                found: Vec::new(),
+                found_style,
+                entries,
+            }));
+
+            let highlighter = Highlighter { editor, state };
+            highlighter.install();
+            highlighter.rehighlight();
+
+            // region Restyle just the touched lines when the text changes.
+            // An edit that comes in while the state is in use can't be
+            // lined up, so the whole text is highlighted again once it is free.
+            let state_mod = highlighter.state.clone();
+            let editor_mod = highlighter.editor.clone();
+            let behind = Rc::new(Cell::new(false));
+            let mut buf_mod = buf;
+            buf_mod.add_modify_callback(move |pos, inserted, deleted, _, _| {
+                if inserted == 0 && deleted == 0 || behind.get() {
+                    return;
+                }
+                match state_mod.try_borrow_mut() {
+                    Ok(mut st) => st.edited(pos as usize, inserted as usize, deleted as usize),
+                    Err(_) => {
+                        behind.set(true);
+                        Highlighter::catch_up(state_mod.clone(), editor_mod.clone(), behind.clone());
+                    }
+                }
+            });
+            // endregion
+
+            highlighter
+        }
+
+        /// Highlights the whole text again as soon as `state` is free, then
+        /// lets the modify callback go back to restyling single edits.
+        fn catch_up(state: Rc<RefCell<HighlightState>>, editor: TextEditor, behind: Rc<Cell<bool>>) {
+            app::add_timeout3(0.0, move |_| match state.try_borrow_mut() {
+                Ok(mut st) => {
+                    st.highlight_all();
+                    behind.set(false);
+                    editor.clone().redraw();
+                }
+                Err(_) => Highlighter::catch_up(state.clone(), editor.clone(), behind.clone()),
+            });
+        }
+
+        /// Builds the style table:  plain text, one entry per rule style,
+        /// then find matches.
+        fn style_entries(editor: &TextEditor, rules: &SyntaxRules) -> Vec<text::StyleTableEntry> {
+            let entry = |color: Color, font: Option<Font>| text::StyleTableEntry {
+                color,
+                font: font.unwrap_or(editor.text_font()),
+                size: editor.text_size(),
+            };
+            let mut entries = vec![entry(editor.text_color(), None)];
+            entries.extend(rules.styles.iter().map(|&(color, font)| entry(color, font)));
+            entries.push(entry(Color::from_rgb(200, 0, 0), None));
+            entries
+        }
+
+        /// Gives the editor this highlighter's style buffer and table.
+        fn install(&self) {
+            let (style, entries) = {
+                let st = self.state.borrow();
+                (st.style.clone(), st.entries.clone())
+            };
+            let mut editor = self.editor.clone();
+            editor.set_highlight_data(style, entries);
+        }
+
+        /// Highlights the whole text again.
+        pub fn rehighlight(&self) {
+            self.state.borrow_mut().highlight_all();
+            self.editor.clone().redraw();
+        }
+
+        /// Swaps in a new set of rules and highlights the text with them.
+        pub fn set_rules(&mut self, rules: SyntaxRules) {
+            let entries = Highlighter::style_entries(&self.editor, &rules);
+            {
+                let mut st = self.state.borrow_mut();
+                st.found_style = b'A' + rules.styles.len() as u8 + 1;
+                st.rules = rules;
+                st.entries = entries;
+            }
+            self.install();
+            self.rehighlight();
+        }
+
+        /// Marks `ranges` as find matches on top of the highlighting.
+        fn set_found(&self, ranges: &[(usize, usize)]) {
+            let mut st = self.state.borrow_mut();
+            st.found = ranges.to_vec();
+            // If the text just changed and the highlighter hasn't caught up
+            // yet, it writes the matches itself when it does.
+            if st.base.len() == st.buf.length() as usize {
+                st.write_all();
+            }
+        }
+    }
+
+    /// Returns the status bar text for `edtr`:  the cursor's line and
+    /// column, the length of any selection, and `words`.
+    fn editor_status(edtr: &TextEditor, words: usize) -> String {
+        let Some(buf) = edtr.buffer() else { return String::new() };
+        let pos = edtr.insert_position();
+        let line = buf.count_lines(0, pos) + 1;
+        let before = buf.text_range(buf.line_start(pos), pos).unwrap_or_default();
+        let col = text_column(&before, buf.tab_distance().max(1) as usize) + 1;
+
+        let mut status = format!("Line {}, Col {}", line, col);
+        let selected = buf.selection_text().chars().count();
+        if selected > 0 {
+            status.push_str(&format!("   |   {} selected", selected));
+        }
+        let plural = if words == 1 { "" } else { "s" };
+        status.push_str(&format!("   |   {} word{}", words, plural));
+        status
+    }
+
+    /// Returns the column that `before`, the start of a line, ends at.
+    /// Each character is one column, and a tab runs on to the next
+    /// multiple of `tab`, the way the editor draws it.
+    fn text_column(before: &str, tab: usize) -> usize {
+        before.chars().fold(0, |col, c| if c == '\t' { (col / tab + 1) * tab } else { col + 1 })
+    }
+
+    /// Asks for a line number and moves the cursor to the start of that line.
+    fn editor_go_to_line(edtr: &mut TextEditor) {
+        let Some(buf) = edtr.buffer() else { return };
+        let lines = buf.count_lines(0, buf.length()) + 1;
+        let Some(answer) = dialog::input_default(&format!("Go to line (1 - {}):", lines), "") else { return };
+        match answer.trim().parse::<i32>() {
+            Ok(line) if line >= 1 => {
+                let mut pos = 0;
+                for _ in 1..line.min(lines) {
+                    match buf.find_char_forward(pos, '\n') {
+                        Some(newline) => pos = newline + 1,
+                        None => break,
+                    }
+                }
+                edtr.set_insert_position(pos);
+                edtr.show_insert_position();
+                let _ = edtr.take_focus();
+            }
+            _ => dialog::beep(dialog::BeepType::Error),
+        }
+    }
+
+    /// Lays out a widget's parent again if it is a `Flex`, after the
+    /// widget has been shown or hidden.
+    fn relayout_parent<W: WidgetExt>(widget: &W) {
+        if let Some(flex) = widget.parent().and_then(|parent| group::Flex::from_dyn_widget(&parent)) {
+            flex.layout();
+        }
+        if let Some(mut win) = widget.window() {
+            win.redraw();
+        }
+    }
+
+    /// Creates a popup window that contains two buttons.
+    ///
+    /// Example:
+    ///
+    ///     use fltk::{app, window};
+    ///     use fltk::enums::Color;
+    ///     use fltk::prelude::{GroupExt, WidgetBase, WidgetExt};
+    ///     use lib_myfltk::fltkutils::*;
+    ///
+    ///     fn main() {
+    ///         let app = app::App::default();
+    ///
+    ///         let mut primwin = window::Window::new(1000, 100, 700, 850, "Two Button Popup Example");
+    ///         primwin.set_color(Color::Yellow);
+    ///         primwin.end();
+    ///         primwin.show();
+    ///
+    ///         let bttn1click = || {
+    ///             println!("\n Button 1 was clicked \n");
+    ///         };
+    ///
+    ///         let bttn2click = || {
+    ///             println!("\n Button 2 was clicked \n");
+    ///         };
+    ///
+    ///         let mut popup = fltk_popup_2btn(&primwin, Box::new(bttn1click), "Button 1",
+    ///                         Box::new(bttn2click), "Button 2");
+    ///
+    ///         popup.end();
+    ///         popup.show();
+    ///
+    ///         app.run().unwrap();
+    ///     }
+    ///
+    ///
+    pub fn fltk_popup_2btn(primwin: &Window, mut closure1: Box<dyn FnMut() + 'static>, label1: &str,
+                           mut closure2: Box<dyn FnMut() + 'static>, label2: &str) -> Window
+    {
+
+        // region Calculate the window position -- tied to the primary window.
+        let win_center = fltk_find_center_wndw(primwin);
+        let popwidth = 575;  // popwidth & popheight are set to accomodate the size of the buttons.
+        let popheight = 100;
+
+        let xxx = win_center.0 - popwidth / 2;
+        let yyy = win_center.1 - popheight / 2;
+        // endregion
+
+        // region Create the popup window with buttons
+        let popwin = Window::default().with_size(popwidth, popheight).with_pos(xxx, yyy);
+
+        let mut but1 = Button::new(25, 25, 250, 40, label1);
+        let mut but2 = Button::new(300, 25, 250, 40, label2);
+        // endregion
+
+        // region Do the button callbacks
+        let mut winclone1 = popwin.clone();
+        but1.set_callback(move |_| {
+            closure1();
+            winclone1.hide();
+        });
+
+        let mut winclone2 = popwin.clone();
+        but2.set_callback(move |_| {
+            closure2();
+            winclone2.hide();
+        });
+        // endregion
+
+        popwin
+    }
+
+    /// Returns the coordinates of the center of `win`.
+    ///
+    pub fn fltk_find_center_wndw(win: &Window) -> (i32, i32) {
+        let xxx = win.x();
+        let yyy = win.y();
+        let www = win.w();
+        let hhh = win.h();
+
+        // Calculate the center position of primwin
+        let center_x = xxx + www / 2;
+        let center_y = yyy + hhh / 2;
+
+        (center_x, center_y)
+    }
+    
+    /// Returns the dimensions of a button given a label.
+    ///
+    ///     #Example for  fltk_size_bttn_to_fit_label  &  fltk_center_button_in_win
+    /// 
+    ///      // The goal is to display a button centered in a window with both sized dynamically.
+    ///
+    ///     use fltk::{prelude::*, app, window::Window};
+    ///     use fltk::app::{set_font, set_font_size};
+    ///     use fltk::{button::Button, enums::Font};
+    ///     use lib_myfltk::fltkutils::{fltk_center_button_in_win, fltk_size_bttn_to_fit_label};
+    ///
+    ///     const FONT: Font = Font::Helvetica;
+    ///     const FONT_SIZE: i32 = 20;
+    ///     const BTTN_PADDING: i32 = 30;
+    ///
+    ///     fn main() {
+    ///         let app = app::App::default();
+    ///
+    ///         set_font(FONT);
+    ///         set_font_size(FONT_SIZE);   // Set font & size to whatever you like best.
+    ///
+    ///        //let mut win1 = Window::default().with_size(40, 20);
+    ///        let label = "123113333zzzzzzzzzzzffffffffffhhhhhhhhhhsdsdfdsfhj";
+    ///
+    ///        // Find the dimensions of the button label.
+    ///        let (bttn_width, bttn_height) = fltk_size_bttn_to_fit_label(&label);
+    ///
+    ///        let mut win = Window::default().with_size(400, 200);
+    ///
+    ///        // Set the dimensions of the window to fit the button.
+    ///        // (Note that the window can be any size you like as long as the button fits.)
+    ///        let win_width = bttn_width + BTTN_PADDING * 2;
+    ///        let win_height = bttn_height + BTTN_PADDING * 2;
+    ///        win.set_size(win_width, win_height);
+    ///
+    ///        // Create the button.
+    ///        let mut bttn = Button::default().with_size(bttn_width, bttn_height).with_label(label);
+    ///
+    ///        // Center the button in the window.
+    ///        let (xxx, yyy) = fltk_center_button_in_win(&win, &bttn);
+    ///        bttn.set_pos(xxx, yyy);
+    ///
+    ///        win.end();
+    ///        win.show();
+    ///
+    ///        app.run().unwrap();
+    ///     }
+    ///
+     pub fn fltk_size_bttn_to_fit_label(label: &str) -> (i32, i32) {
+
+        const FONT: Font = Font::Helvetica;
+        const BTTN_LABEL_PADDING: i32 = 10;
+
+        let mut win = Window::default().with_size(40, 20);
+
+        win.show();   // Show the window so you can measure the label.  Note that
+        // the only reason you need to show the window is to measure the label.
+        // The size of the window doesn't matter as far as the button is concerned.
+        draw::set_font(FONT, app::font_size());  // Also needed so you can measure the label.
+        let (label_len, label_height) = measure(&label, false);
+
+        let bttn_width = label_len + BTTN_LABEL_PADDING * 2;
+        let bttn_height = label_height + BTTN_LABEL_PADDING * 2;
+
+        win.hide();
+        (bttn_width, bttn_height)
+    }
+
+    /// Returns the coordinates for positioning a button centered relative to
+    /// a window.  I.e. (0,0) is the top left corner of the window.  The coordinates
+    /// returned by this function are for the top, left positon of the centered button.
+    pub fn fltk_center_button_in_win(win: &Window, bttn: &Button) -> (i32, i32) {
+
+        // Find coordinates to center the button both vertically and horizontally
+        let (centerx, centery) = fltk_find_center_wndw(&win);
+        let xxx = centerx - (bttn.w() / 2);
+        let yyy = centery - (bttn.h() / 2);
+
+        (xxx, yyy)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn history(limit: usize) -> HistoryState {
+            HistoryState {
+                undo: VecDeque::new(),
+                redo: Vec::new(),
+                last_kind: StepKind::Other,
+                depth: 0,
+                replaying: false,
+                limit,
+            }
+        }
+
+        fn change(pos: usize, inserted: &str, deleted: &str) -> BufferChange {
+            BufferChange { pos, inserted: inserted.to_string(), deleted: deleted.to_string() }
+        }
+
+        #[test]
+        fn history_merges_typing_a_word_at_a_time() {
+            let mut st = history(500);
+            for (pos, ch) in "cat sat".chars().enumerate() {
+                st.record(change(pos, &ch.to_string(), ""));
+            }
+            let steps: Vec<String> = st.undo.iter().map(|step| step[0].inserted.clone()).collect();
+            assert_eq!(steps, vec!["cat ", "sat"]);     // The space ends the first word.
+
+            st.record(change(20, "x", ""));     // Typing somewhere else starts a new step.
+            assert_eq!(st.undo.len(), 3);
+            st.record(change(0, "pasted", ""));
+            assert_eq!(st.undo.len(), 4);
+        }
+
+        #[test]
+        fn history_merges_backspace_and_delete_runs() {
+            let mut st = history(500);
+            st.record(change(9, "", "c"));     // Backspace from 10 back to 7.
+            st.record(change(8, "", "b"));
+            st.record(change(7, "", "a"));
+            assert_eq!(st.undo.len(), 1);
+            assert_eq!((st.undo[0][0].pos, st.undo[0][0].deleted.as_str()), (7, "abc"));
+
+            st.record(change(2, "", "x"));     // Delete at 2, twice.
+            st.record(change(2, "", "y"));
+            assert_eq!(st.undo.len(), 2);
+            assert_eq!((st.undo[1][0].pos, st.undo[1][0].deleted.as_str()), (2, "xy"));
+        }
+
+        #[test]
+        fn history_keeps_transactions_together_and_drops_the_oldest_steps() {
+            let mut st = history(3);
+            st.redo.push(vec![change(0, "old", "")]);
+            st.depth = 1;
+            st.undo.push_back(Vec::new());
+            st.record(change(0, "a", ""));
+            st.record(change(5, "", "bcd"));
+            st.depth = 0;
+            assert_eq!(st.undo.len(), 1);
+            assert_eq!(st.undo[0].len(), 2);
+            assert!(st.redo.is_empty());     // A new change clears redo.
+
+            for pos in 0..4 {
+                st.record(change(pos * 10, "word", ""));
+            }
+            assert_eq!(st.undo.len(), 3);
+            assert_eq!(st.undo[0][0].pos, 10);     // The transaction and the first word are gone.
+        }
+
+        #[test]
+        fn find_pattern_follows_the_settings() {
+            assert!(find_pattern("", true, true, true).unwrap().is_none());
+            let text = "Cat cat concat (cat)";
+            let re = find_pattern("cat", false, false, false).unwrap().unwrap();
+            assert_eq!(find_matches(&re, text), vec![(0, 3), (4, 7), (11, 14), (16, 19)]);
+            let re = find_pattern("cat", true, true, false).unwrap().unwrap();
+            assert_eq!(find_matches(&re, text), vec![(4, 7), (16, 19)]);
+            let re = find_pattern("(cat)", true, false, false).unwrap().unwrap();
+            assert_eq!(find_matches(&re, text), vec![(15, 20)]);     // Not a regex, so the brackets are literal.
+            let re = find_pattern("c.t|a*", true, false, true).unwrap().unwrap();
+            assert_eq!(find_matches(&re, text).len(), 4);     // The "a" of "Cat", but no empty matches of a*.
+            assert!(find_pattern("(cat", true, false, true).is_err());
+        }
+
+        #[test]
+        fn matches_follow_edits() {
+            let mut ranges = vec![(0, 3), (10, 13), (20, 23)];
+            shift_ranges(&mut ranges, 5, 4, 0);     // Typing between matches.
+            assert_eq!(ranges, vec![(0, 3), (14, 17), (24, 27)]);
+            shift_ranges(&mut ranges, 15, 0, 1);     // Deleting inside a match drops it.
+            assert_eq!(ranges, vec![(0, 3), (23, 26)]);
+            shift_ranges(&mut ranges, 3, 1, 0);     // Typing right after a match keeps it.
+            assert_eq!(ranges, vec![(0, 3), (24, 27)]);
+            shift_ranges(&mut ranges, 0, 2, 0);     // Typing right before a match moves it.
+            assert_eq!(ranges, vec![(2, 5), (26, 29)]);
+        }
+
+        #[test]
+        fn replacements_expand_only_in_regex_mode() {
+            let text = "size = 10, count = 20";
+            let re = find_pattern(r"(\w+) = (\d+)", true, false, true).unwrap().unwrap();
+            assert_eq!(replacement_at(&re, true, "$2 $1", text, 11), "20 count");
+            assert_eq!(replacement_at(&re, false, "$2 $1", text, 11), "$2 $1");
+            assert_eq!(replace_matches(&re, true, "$1: $2", text), "size: 10, count: 20");
+            let re = find_pattern("= ", true, false, false).unwrap().unwrap();
+            assert_eq!(replace_matches(&re, false, "$1", text), "size $110, count $120");
+        }
+
+        #[test]
+        fn row_keys_stay_inside_the_list() {
+            assert_eq!(row_for_key(Key::Up, 0, 10, 4), Some(0));
+            assert_eq!(row_for_key(Key::Down, 9, 10, 4), Some(9));
+            assert_eq!(row_for_key(Key::Down, 3, 10, 4), Some(4));
+            assert_eq!(row_for_key(Key::PageDown, 8, 10, 4), Some(9));
+            assert_eq!(row_for_key(Key::PageUp, 6, 10, 4), Some(2));
+            assert_eq!(row_for_key(Key::PageUp, 2, 10, 4), Some(0));
+            assert_eq!(row_for_key(Key::Home, 6, 10, 4), Some(0));
+            assert_eq!(row_for_key(Key::End, 0, 10, 4), Some(9));
+            assert_eq!(row_for_key(Key::Enter, 5, 10, 4), None);
+        }
+
+        #[test]
+        fn scrolling_keeps_the_row_in_view() {
+            assert_eq!(top_row_for(5, 3, 4), 3);    // Already showing.
+            assert_eq!(top_row_for(6, 3, 4), 3);
+            assert_eq!(top_row_for(7, 3, 4), 4);    // Just below, so scroll by one.
+            assert_eq!(top_row_for(20, 3, 4), 17);
+            assert_eq!(top_row_for(1, 3, 4), 1);    // Above, so it goes to the top.
+        }
+
+        #[test]
+        fn checklist_state_handles_10_000_items_quickly() {
+            let start = std::time::Instant::now();
+            let labels: Vec<String> = (1..=10_000).map(|n| format!("Question {}", n)).collect();
+            let mut st = ChecklistState::new(labels);
+            for idx in (0..10_000).step_by(3) {
+                st.toggle(idx);
+            }
+            st.filter("question 99", MatchMode::CaseInsensitive);
+            let shown = st.shown.len();
+            st.filter("", MatchMode::Fuzzy);
+            let checked = st.checked();
+            let took = start.elapsed();
+
+            assert_eq!(shown, 111);    // 99, 990-999 and 9900-9999.
+            assert_eq!(checked.len(), 3334);
+            assert_eq!(st.shown.len(), 10_000);
+            assert!(took.as_millis() < 100, "took {:?}", took);
+        }
+
+        #[test]
+        fn single_checklist_keeps_one_check() {
+            let mut st = ChecklistState::new((0..130).map(|n| n.to_string()).collect());
+            st.toggle(3);
+            st.toggle(70);
+            assert_eq!(st.checked(), vec![3, 70]);
+            st.single = true;
+            st.toggle(129);
+            assert_eq!(st.checked(), vec![129]);
+            st.toggle(129);
+            assert!(st.checked().is_empty());
+        }
+
+        #[test]
+        fn match_modes_treat_case_and_gaps_differently() {
+            for mode in [MatchMode::Substring, MatchMode::CaseInsensitive, MatchMode::Fuzzy] {
+                assert!(mode.matches("", "anything"), "{:?}", mode);
+                assert!(mode.matches("gebr", "Algebra"), "{:?}", mode);
+                assert!(!mode.matches("x", "Algebra"), "{:?}", mode);
+            }
+            assert!(!MatchMode::Substring.matches("ALG", "Algebra"));
+            assert!(MatchMode::CaseInsensitive.matches("ALG", "Algebra"));
+            assert!(!MatchMode::CaseInsensitive.matches("agb", "Algebra"));
+            assert!(MatchMode::Fuzzy.matches("AGB", "Algebra"));
+            assert!(!MatchMode::Fuzzy.matches("bga", "Algebra"));   // Letters must come in order.
+            assert!(!MatchMode::Fuzzy.matches("aaa", "Algebra"));   // Each letter is used once.
+            assert!(MatchMode::Fuzzy.matches("ÉT", "été"));
+        }
+
+        /// Whether every byte of the first `part` of `line` gets rule style `style`.
+        fn painted(rules: &SyntaxRules, line: &str, part: &str, style: usize) -> bool {
+            let at = line.find(part).unwrap();
+            rules.style_line(line)[at..at + part.len()].iter().all(|&b| b == b'A' + style as u8)
+        }
+
+        #[test]
+        fn style_line_paints_later_rules_over_earlier_ones() {
+            let rules = SyntaxRules::default()
+                .with_pattern(r"\w+", Color::Blue, None).unwrap()
+                .with_keywords(&["fn"], Color::Red, None).unwrap();
+            let line = "fn main";
+            assert_eq!(rules.style_line(line), b"CCABBBB".to_vec());
+            assert_eq!(rules.style_line(""), Vec::<u8>::new());
+        }
+
+        #[test]
+        fn style_line_paints_only_the_first_capture_group() {
+            let rules = SyntaxRules::default().with_pattern(r"x(\d+)", Color::Blue, None).unwrap();
+            assert_eq!(rules.style_line("ax12b"), b"AABBA".to_vec());
+        }
+
+        #[test]
+        fn style_line_clips_closure_ranges_to_the_line() {
+            let rules = SyntaxRules::default()
+                .with_closure(|_| vec![2..99, 1..1], Color::Blue, None).unwrap();
+            assert_eq!(rules.style_line("abcd"), b"AABB".to_vec());
+        }
+
+        #[test]
+        fn text_column_expands_tabs() {
+            assert_eq!(text_column("", 8), 0);
+            assert_eq!(text_column("abc", 8), 3);
+            assert_eq!(text_column("\t", 8), 8);
+            assert_eq!(text_column("ab\tc", 4), 5);
+            assert_eq!(text_column("abcd\t", 4), 8);
+            assert_eq!(text_column("é\t", 8), 8);
+        }
+
+        #[test]
+        fn markdown_tells_italic_from_bold() {
+            let md = SyntaxRules::markdown();
+            let line = "some *italic* and **bold** text";
+            assert!(painted(&md, line, "*italic*", 3));
+            assert!(painted(&md, line, "**bold**", 2));
+            assert!(painted(&md, line, " and ", 0));
+            assert!(painted(&md, "# Heading *x*", "# Heading *x*", 7));
+            assert!(painted(&md, "- item `code`", "-", 1));
+            assert!(painted(&md, "- item `code`", " item ", 0));
+            assert!(painted(&md, "- item `code`", "`code`", 5));
+            assert!(painted(&md, "see [docs](http://x)", "[docs](http://x)", 4));
+        }
+
+        #[test]
+        fn toml_comment_starts_after_quoted_hash() {
+            let toml = SyntaxRules::toml();
+            let line = r##"name = "a # b"  # The name"##;
+            assert!(painted(&toml, line, "name", 3));
+            assert!(painted(&toml, line, r#""a # b""#, 2));
+            assert!(painted(&toml, line, "# The name", 5));
+            assert!(painted(&toml, "[dependencies]", "[dependencies]", 4));
+            assert!(painted(&toml, "size = 1_000", "1_000", 1));
+            assert!(painted(&toml, "on = true", "true", 1));
+        }
+
+        #[test]
+        fn json_keys_paint_over_strings() {
+            let json = SyntaxRules::json();
+            let line = r#"{"key": "value:", "n": -12.5, "ok": null}"#;
+            assert!(painted(&json, line, r#""key""#, 3));
+            assert!(painted(&json, line, r#""value:""#, 2));
+            assert!(painted(&json, line, r#""n""#, 3));
+            assert!(painted(&json, line, "-12.5", 1));
+            assert!(painted(&json, line, "null", 1));
+        }
+
+        #[test]
+        fn placeholders_need_a_name() {
+            let vars = SyntaxRules::placeholders();
+            let line = "Hi {name}, {1bad} {}";
+            assert!(painted(&vars, line, "{name}", 1));
+            assert!(painted(&vars, line, "{1bad}", 0));
+            assert!(painted(&vars, line, "{}", 0));
+        }
+
+        #[test]
+        fn add_style_stops_at_the_cap() {
+            let mut rules = SyntaxRules::default();
+            for _ in 0..SyntaxRules::MAX_STYLES {
+                rules = rules.with_pattern("x", Color::Blue, None).unwrap();
+            }
+            assert!(matches!(rules.clone().with_keywords(&["x"], Color::Red, None), Err(SyntaxRulesError::TooManyStyles)));
+            assert!((b'A' as usize + SyntaxRules::MAX_STYLES + 1) < 128);
+            assert!(matches!(SyntaxRules::default().with_pattern("(", Color::Red, None), Err(SyntaxRulesError::Pattern(_))));
+        }
+    }
+
+}
+
+/// Input functions for FLTK-RS using the `fltk::input` module.
+///  
+pub mod input_fltk {
+
+/*
+// todo: Still getting "unused" warnings.  Fix it if you can figure out how.
+
+*/  // TODO's
+
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+use std::str::FromStr;
+use fltk::app::{self, App};
+use fltk::{browser, button, dialog, draw, frame, group, input, output, widget, window};
+use fltk::enums::{Align, CallbackTrigger, Color, Event, Font, FrameType, Key};
+use fltk::prelude::{BrowserExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt};
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The ways a `try_input_*` prompt can fail to return a value.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputError {
+    /// The user clicked Cancel, pressed Escape, or closed the window
+    /// with the title bar close button.
+    Cancelled,
+    /// The user submitted an empty field.
+    Empty,
+    /// The text could not be converted to the requested type.
+    /// Carries the raw text the user typed.
+    ParseFailed(String),
+    /// The window went away before the user answered, e.g. because
+    /// the app's event loop was shut down with `app::quit()`.
+    WindowClosed,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Cancelled => write!(f, "input was cancelled"),
+            InputError::Empty => write!(f, "no value was entered"),
+            InputError::ParseFailed(text) => write!(f, "could not read a value from \"{}\"", text),
+            InputError::WindowClosed => write!(f, "the input window was closed"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// The check behind a `Rule::Custom`.
+type RuleCheck = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+/// A constraint that can be attached to a prompt with
+/// `input_parse_with_rules()`.  The rules are checked as the user types.
+/// The first rule that fails has its message shown under the field, and
+/// OK stays greyed out until every rule passes.
+///
+/// Apart from `NonEmpty`, the rules let an empty field through, so add
+/// `NonEmpty` if a value is required.
+///
+#[derive(Clone)]
+pub enum Rule {
+    /// The text must be a number no smaller than this.
+    Min(f64),
+    /// The text must be a number no larger than this.
+    Max(f64),
+    /// The field can't be left empty.
+    NonEmpty,
+    /// The text can't be longer than this many characters.
+    MaxLen(usize),
+    /// The text must match this regular expression.  Use `^` and `$`
+    /// if the whole text has to match.
+    Matches(Regex),
+    /// The text must be one of these values.
+    OneOf(Vec<String>),
+    /// Any other check.  Return `Err` with the message to show the user.
+    Custom(RuleCheck),
+}
+
+impl Rule {
+    /// Builds a `Rule::Matches` from a regular expression pattern.
+    pub fn matches(pattern: &str) -> Result<Rule, regex::Error> {
+        Ok(Rule::Matches(Regex::new(pattern)?))
+    }
+
+    /// Builds a `Rule::Custom` from a closure.
+    pub fn custom<F>(check: F) -> Rule
+        where F: Fn(&str) -> Result<(), String> + 'static
+    {
+        Rule::Custom(Rc::new(check))
+    }
+
+    /// Checks `text` against this rule.  Returns the message to show the
+    /// user if it fails.
+    pub fn check(&self, text: &str) -> Result<(), String> {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return match self {
+                Rule::NonEmpty => Err("A value is required.".to_string()),
+                _ => Ok(()),
+            };
+        }
+
+        match self {
+            Rule::Min(min) => match trimmed.parse::<f64>() {
+                Ok(num) if num < *min => Err(format!("The value can't be less than {}.", min)),
+                Ok(_) => Ok(()),
+                Err(_) => Err("Please enter a number.".to_string()),
+            },
+            Rule::Max(max) => match trimmed.parse::<f64>() {
+                Ok(num) if num > *max => Err(format!("The value can't be more than {}.", max)),
+                Ok(_) => Ok(()),
+                Err(_) => Err("Please enter a number.".to_string()),
+            },
+            Rule::NonEmpty => Ok(()),
+            Rule::MaxLen(len) => {
+                if text.chars().count() > *len {
+                    Err(format!("The value can't be longer than {} characters.", len))
+                } else {
+                    Ok(())
+                }
+            }
+            Rule::Matches(regex) => {
+                if regex.is_match(text) {
+                    Ok(())
+                } else {
+                    Err(format!("The value must match the pattern {}", regex.as_str()))
+                }
+            }
+            Rule::OneOf(choices) => {
+                if choices.iter().any(|choice| choice == trimmed) {
+                    Ok(())
+                } else {
+                    Err(format!("The value must be one of:  {}", choices.join(", ")))
+                }
+            }
+            Rule::Custom(check) => check(text),
+        }
+    }
+}
+
+/// Checks `text` against each of the `rules` in turn.  Returns the
+/// message from the first rule that fails.
+pub fn check_rules(rules: &[Rule], text: &str) -> Result<(), String> {
+    rules.iter().try_for_each(|rule| rule.check(text))
+}
+
+/// Settings for the prompt windows.  Every prompt that has a `_with`
+/// version takes one of these.  Start from `InputOptions::default()` and
+/// change what you need with the `with_*` methods.
+///
+/// Example:
+///
+///     use fltk::enums::{Color, Font};
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = fltk::app::App::default();
+///
+///     let opts = InputOptions::default()
+///         .with_title("Edit Question")
+///         .with_initial("42")
+///         .with_placeholder("points")
+///         .with_size(500, 160)
+///         .with_font(Font::Times, 18)
+///         .with_colors(Color::Black, Color::Cyan, Color::White);
+///
+///     let points = try_input_i64_with(&app, "Points for this question?", &opts);
+///
+///     println!("\n points: {:?} \n", points);
+///
+#[derive(Clone)]
+pub struct InputOptions {
+    /// The window title.
+    pub title: String,
+    /// Text to put in the field when the window opens.
+    pub initial: String,
+    /// Grey hint text shown while the field is empty.
+    pub placeholder: String,
+    /// Window size.  `None` lets each prompt use its own default size.
+    pub size: Option<(i32, i32)>,
+    /// Window position.  `None` lets the window manager place it.
+    pub pos: Option<(i32, i32)>,
+    /// Font used for the prompt and the field.
+    pub font: Font,
+    /// Font size used for the prompt and the field.
+    pub font_size: i32,
+    /// Color of the text in the field.
+    pub text_color: Color,
+    /// Background color of the window.
+    pub win_color: Color,
+    /// Background color of the field.
+    pub field_color: Color,
+    /// Rules checked as the user types.  See `Rule`.
+    pub rules: Vec<Rule>,
+}
+
+impl Default for InputOptions {
+    fn default() -> Self {
+        InputOptions {
+            title: "Input Window".to_string(),
+            initial: String::new(),
+            placeholder: String::new(),
+            size: None,
+            pos: None,
+            font: Font::Helvetica,
+            font_size: 14,
+            text_color: Color::Foreground,
+            win_color: Color::Background,
+            field_color: Color::Background2,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl InputOptions {
+    /// Sets the window title.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Sets the text in the field when the window opens.
+    pub fn with_initial(mut self, initial: &str) -> Self {
+        self.initial = initial.to_string();
+        self
+    }
+
+    /// Sets the grey hint text shown while the field is empty.
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    /// Sets the window size.
+    pub fn with_size(mut self, width: i32, height: i32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Sets the window position.
+    pub fn with_pos(mut self, x: i32, y: i32) -> Self {
+        self.pos = Some((x, y));
+        self
+    }
+
+    /// Sets the font and font size.
+    pub fn with_font(mut self, font: Font, font_size: i32) -> Self {
+        self.font = font;
+        self.font_size = font_size;
+        self
+    }
+
+    /// Sets the text, window and field colors.
+    pub fn with_colors(mut self, text_color: Color, win_color: Color, field_color: Color) -> Self {
+        self.text_color = text_color;
+        self.win_color = win_color;
+        self.field_color = field_color;
+        self
+    }
+
+    /// Sets the rules checked as the user types.
+    pub fn with_rules(mut self, rules: &[Rule]) -> Self {
+        self.rules = rules.to_vec();
+        self
+    }
+}
+
+/// Creates a prompt window set up from `opts`.  `default_size` is used
+/// when `opts` doesn't give a size.
+fn options_window(opts: &InputOptions, default_size: (i32, i32)) -> window::Window {
+    let (width, height) = opts.size.unwrap_or(default_size);
+    let mut win = window::Window::default()
+        .with_size(width, height)
+        .with_label(&opts.title);
+    if let Some((xxx, yyy)) = opts.pos {
+        win.set_pos(xxx, yyy);
+    }
+    win.set_color(opts.win_color);
+    win.make_resizable(true);
+    win
+}
+
+/// Applies the font, colors, initial value and placeholder in `opts`
+/// to an input widget.
+fn style_input_widget<W>(input_widget: &mut W, opts: &InputOptions)
+    where W: InputExt + WidgetBase
+{
+    input_widget.set_text_font(opts.font);
+    input_widget.set_text_size(opts.font_size);
+    input_widget.set_text_color(opts.text_color);
+    input_widget.set_color(opts.field_color);
+    input_widget.set_value(&opts.initial);
+
+    // FLTK's input widgets have no placeholder, so draw it over the empty field.
+    if !opts.placeholder.is_empty() {
+        let placeholder = opts.placeholder.clone();
+        input_widget.draw(move |i| {
+            if i.value().is_empty() {
+                draw::set_font(i.text_font(), i.text_size());
+                draw::set_draw_color(Color::Inactive);
+                draw::draw_text2(&placeholder, i.x() + 4, i.y(), i.w() - 8, i.h(), Align::Left);
+            }
+        });
+    }
+}
+
+/// Applies the font and size in `opts` to a prompt label.
+fn style_prompt_label(label: &mut frame::Frame, opts: &InputOptions) {
+    label.set_label_font(opts.font);
+    label.set_label_size(opts.font_size);
+}
+
+    /// Allows the user to input a vector of Strings.
+///
+/// Opens the `input_vec()` list editor.  The window is made at least
+/// `horiz` x `vert` pixels.  Returns an empty vector if the user cancels.
+pub fn input_strvec(app: &App, prompt: &str, horiz: i32, vert: i32) -> Vec<String> {
+    let opts = InputOptions::default()
+        .with_title("List Editor")
+        .with_size(horiz.max(500), vert.max(420));
+    input_vec_with::<String>(app, prompt, &opts).unwrap_or_default()
+}
+
+/// Allows the user to input a vector of f64 integers.
+///
+/// Opens the `input_vec()` list editor.  Returns an empty vector if the user cancels.
+pub fn input_f64vec(app: &App, prompt: &str) -> Vec<f64> {
+    input_vec::<f64>(app, prompt).unwrap_or_default()
+}
+
+/// Allows the user to input a vector of characters.
+///
+/// Opens the `input_vec()` list editor.  Returns an empty vector if the user cancels.
+pub fn input_charvec(app: &App, prompt: &str) -> Vec<char> {
+    input_vec::<char>(app, prompt).unwrap_or_default()
+}
+
+/// Allows the user to input a vector of i64 integers.
+///
+/// Opens the `input_vec()` list editor.  Returns an empty vector if the user cancels.
+pub fn input_i64vec(app: &App, prompt: &str) -> Vec<i64> {
+    input_vec::<i64>(app, prompt).unwrap_or_default()
+}
+
+/// Opens a single list editor window that lets the user build a vector
+/// of any type that implements `FromStr`.
+///
+/// Type a value and press Enter (or click Add) to add it after the
+/// selected row.  Clicking a row copies it into the edit field, where it
+/// can be changed and written back with Update.  Remove, Up and Down work
+/// on the selected row.  Every row is parsed as it is entered, and bad
+/// values are reported in red without being added.  Paste... and
+/// Import... bring in a whole list at once, either pasted or read from a
+/// text or CSV file; see `split_list_text()`.  Done returns the
+/// finished list; Cancel, Escape or the close button return
+/// `InputError::Cancelled`.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = fltk::app::App::default();
+///
+///     let scores: Vec<i64> = input_vec(&app, "Enter the test scores.").unwrap_or_default();
+///
+///     println!("\n scores: {:?} \n", scores);
+///
+pub fn input_vec<T>(app: &App, prompt: &str) -> Result<Vec<T>, InputError>
+    where T: FromStr + 'static,
+          T::Err: fmt::Display
+{
+    input_vec_with(app, prompt, &InputOptions::default().with_title("List Editor"))
+}
+
+/// Same as `input_vec()`, set up from `opts`.  The `initial` text is split
+/// with `split_list_text()` to pre-fill the list, and the `rules` are
+/// checked against every row.
+pub fn input_vec_with<T>(app: &App, prompt: &str, opts: &InputOptions) -> Result<Vec<T>, InputError>
+    where T: FromStr + 'static,
+          T::Err: fmt::Display
+{
+    run_list_editor::<T>(app, prompt, opts)
+}
+
+/// Redraws the rows of the list editor's browser, selecting `line` (1-based).
+fn refresh_list_browser(browser: &mut browser::HoldBrowser, items: &[String], line: i32) {
+    browser.clear();
+    for item in items {
+        browser.add(&format!("@.{}", item));  // "@." stops FLTK from reading '@' as a format code.
+    }
+    if line > 0 && line <= browser.size() {
+        browser.select(line);
+    }
+}
+
+/// Splits a pasted block of text into list items.  Items can be
+/// separated by new lines, commas or tabs.  As in a CSV file, an item
+/// wrapped in double quotes can hold any of those, and `""` inside it
+/// stands for one quote.  Each item is trimmed, apart from what is inside
+/// the quotes, and empty items are dropped.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::split_list_text;
+///
+///     let items = split_list_text("red, green\tblue\n\nyellow, \"Smith, Jo\"");
+///     assert_eq!(items, vec!["red", "green", "blue", "yellow", "Smith, Jo"]);
+///
+pub fn split_list_text(text: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut quoted_len: Option<usize> = None;   // Set once the item has been in quotes.
+    let mut in_quotes = false;
+
+    let mut finish = |item: &mut String, quoted_len: &mut Option<usize>| {
+        let kept = match quoted_len.take() {
+            Some(len) => &item[..len + item[len..].trim_end().len()],
+            None => item.trim(),
+        };
+        if !kept.is_empty() {
+            items.push(kept.to_string());
+        }
+        item.clear();
+    };
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                item.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => {
+                in_quotes = false;
+                quoted_len = Some(item.len());
+            }
+            '"' if quoted_len.is_none() && item.trim().is_empty() => {
+                in_quotes = true;
+                item.clear();
+            }
+            '\n' | '\r' | ',' | '\t' if !in_quotes => finish(&mut item, &mut quoted_len),
+            _ => item.push(c),
+        }
+    }
+    finish(&mut item, &mut quoted_len);
+    items
+}
+
+/// Shows a window where a block of text can be pasted or edited.  Every
+/// piece of the text is checked as it is typed, and the pieces that fail
+/// are shown in red with the reason.  Returns the good pieces if the user
+/// clicks Add, or `None` if they cancel.
+fn run_import_preview(app: &App, starttext: &str,
+                      check: ItemCheck) -> Option<Vec<String>> {
+
+    // region Set up the window.
+    let mut win = window::Window::default()
+        .with_size(500, 460)
+        .with_label("Paste a List");
+    win.make_resizable(true);
+
+    let mut flex = group::Flex::default_fill().column();
+    flex.set_margin(10);
+    flex.set_pad(8);
+
+    let prompttext = frame::Frame::default()
+        .with_label("Paste your list below.  Items can be separated by new lines, commas or tabs.");
+    flex.fixed(&prompttext, 30);
+
+    let mut pastefield = input::MultilineInput::default();
+    pastefield.set_trigger(CallbackTrigger::Changed);
+    pastefield.set_value(starttext);
+    flex.fixed(&pastefield, 150);
+
+    let mut preview = browser::Browser::default();
+    let mut status = frame::Frame::default();
+    flex.fixed(&status, 20);
+
+    let bttnrow = group::Flex::default().row();
+    let _spacer = frame::Frame::default();
+    let mut add = button::Button::default().with_label("Add");
+    let mut cancel = button::Button::default().with_label("Cancel");
+    bttnrow.end();
+    flex.fixed(&bttnrow, 30);
+
+    flex.end();
+    win.end();
+    win.make_modal(true);
+    win.show();
+    // endregion
+
+    // region Check the pieces every time the text changes.
+    let good: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let good_chng = good.clone();
+    let mut add_chng = add.clone();
+    let mut update_preview = move |text: &str| {
+        preview.clear();
+        good_chng.borrow_mut().clear();
+        let mut badcount = 0;
+        for piece in split_list_text(text) {
+            match check(&piece) {
+                Ok(value) => {
+                    preview.add(&format!("@.{}", piece));
+                    good_chng.borrow_mut().push(value);
+                }
+                Err(msg) => {
+                    preview.add(&format!("@C1@.{}    <-- {}", piece, msg));  // Color 1 is red.
+                    badcount += 1;
+                }
+            }
+        }
+
+        let goodcount = good_chng.borrow().len();
+        if badcount > 0 {
+            status.set_label_color(Color::Red);
+            status.set_label(&format!("{} rows will be added.  {} rows in red will be skipped.", goodcount, badcount));
+        } else {
+            status.set_label_color(Color::Black);
+            status.set_label(&format!("{} rows will be added.", goodcount));
+        }
+        if goodcount > 0 { add_chng.activate(); } else { add_chng.deactivate(); }
+    };
+    update_preview(starttext);
+    pastefield.set_callback(move |p| update_preview(&p.value()));
+    // endregion
+
+    // region Do the Add & Cancel callbacks.
+    let accepted = Rc::new(RefCell::new(false));
+
+    let accepted_add = accepted.clone();
+    let mut win_add = win.clone();
+    add.set_callback(move |_| {
+        *accepted_add.borrow_mut() = true;
+        win_add.hide();
+    });
+
+    let mut win_cancel = win.clone();
+    cancel.set_callback(move |_| win_cancel.hide());
+    // endregion
+
+    while win.shown() {
+        if !app.wait() {
+            break;
+        }
+    }
+    win.hide();
+
+    if *accepted.borrow() {
+        Some(good.borrow().clone())
+    } else {
+        None
+    }
+}
+
+/// Builds and runs the list editor used by `input_vec()`.
+fn run_list_editor<T>(app: &App, prompt: &str, opts: &InputOptions) -> Result<Vec<T>, InputError>
+    where T: FromStr + 'static,
+          T::Err: fmt::Display
+{
+    // region Set up the window & the list of items.
+    let mut win = options_window(opts, (500, 420));
+
+    let mut flex = group::Flex::default_fill().column();
+    flex.set_margin(10);
+    flex.set_pad(8);
+
+    let mut prompttext = frame::Frame::default().with_label(prompt);
+    style_prompt_label(&mut prompttext, opts);
+    flex.fixed(&prompttext, 30);
+
+    let mut listrow = group::Flex::default().row();
+    let mut browser = browser::HoldBrowser::default();
+    let mut sidebttns = group::Flex::default().column();
+    let mut remove = button::Button::default().with_label("Remove");
+    let mut moveup = button::Button::default().with_label("Up");
+    let mut movedown = button::Button::default().with_label("Down");
+    let _spacer = frame::Frame::default();
+    let mut paste = button::Button::default().with_label("Paste...");
+    let mut import = button::Button::default().with_label("Import...");
+    sidebttns.fixed(&remove, 30);
+    sidebttns.fixed(&moveup, 30);
+    sidebttns.fixed(&movedown, 30);
+    sidebttns.fixed(&paste, 30);
+    sidebttns.fixed(&import, 30);
+    sidebttns.end();
+    listrow.fixed(&sidebttns, 90);
+    listrow.end();
+    // endregion
+
+    // region Set up the edit field.
+    let mut editrow = group::Flex::default().row();
+    let mut editfield = input::Input::default();
+    style_input_widget(&mut editfield, &InputOptions { initial: String::new(), ..opts.clone() });
+    editfield.set_trigger(CallbackTrigger::EnterKey);
+    let mut add = button::Button::default().with_label("Add");
+    let mut update = button::Button::default().with_label("Update");
+    editrow.fixed(&add, 70);
+    editrow.fixed(&update, 70);
+    editrow.end();
+    flex.fixed(&editrow, 30);
+
+    let mut errlabel = frame::Frame::default();
+    errlabel.set_label_color(Color::Red);
+    errlabel.set_label_size(12);
+    flex.fixed(&errlabel, 20);
+    // endregion
+
+    // region Set up the Done & Cancel buttons.
+    let bttnrow = group::Flex::default().row();
+    let _spacer = frame::Frame::default();
+    let mut done = button::Button::default().with_label("Done");
+    let mut cancel = button::Button::default().with_label("Cancel");
+    bttnrow.end();
+    flex.fixed(&bttnrow, 30);
+
+    flex.end();
+    win.end();
+    win.show();
+    let _ = editfield.take_focus();
+    // endregion
+
+    // region Set up the shared state.
+    let rules = opts.rules.clone();
+    let check: ItemCheck = Rc::new(move |text: &str| {
+        check_rules(&rules, text)?;
+        parse_or_message::<T>(text).map(|_| text.trim().to_string())
+    });
+
+    // Pre-fill the list with the initial rows that pass the check.
+    let startrows: Vec<String> = split_list_text(&opts.initial).iter()
+        .filter_map(|row| check(row).ok())
+        .collect();
+    refresh_list_browser(&mut browser, &startrows, 0);
+
+    let items: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(startrows));
+    let outcome: Outcome<Vec<T>> = Rc::new(RefCell::new(None));
+    // endregion
+
+    // region Do the callbacks for editing the list.
+    // Clicking a row copies it into the edit field.
+    let items_sel = items.clone();
+    let mut editfield_sel = editfield.clone();
+    let mut errlabel_sel = errlabel.clone();
+    browser.set_callback(move |b| {
+        let line = b.value();
+        if line > 0 {
+            editfield_sel.set_value(&items_sel.borrow()[(line - 1) as usize]);
+        }
+        errlabel_sel.set_label("");
+    });
+
+    // Add puts the new item after the selected row, or at the end of the list.
+    let items_add = items.clone();
+    let check_add = check.clone();
+    let mut browser_add = browser.clone();
+    let mut errlabel_add = errlabel.clone();
+    let mut add_item = move |field: &mut input::Input| {
+        match check_add(&field.value()) {
+            Ok(text) => {
+                let selected = browser_add.value();
+                let pos = if selected > 0 { selected as usize } else { items_add.borrow().len() };
+                items_add.borrow_mut().insert(pos, text);
+                refresh_list_browser(&mut browser_add, &items_add.borrow(), pos as i32 + 1);
+                field.set_value("");
+                errlabel_add.set_label("");
+            }
+            Err(msg) => errlabel_add.set_label(&msg),
+        }
+        let _ = field.take_focus();
+    };
+    let mut editfield_add = editfield.clone();
+    let mut add_item_bttn = add_item.clone();
+    add.set_callback(move |_| add_item_bttn(&mut editfield_add));
+    editfield.set_callback(move |f| add_item(f));
+
+    // Update writes the edit field back over the selected row.
+    let items_upd = items.clone();
+    let check_upd = check.clone();
+    let mut browser_upd = browser.clone();
+    let mut errlabel_upd = errlabel.clone();
+    let editfield_upd = editfield.clone();
+    update.set_callback(move |_| {
+        let line = browser_upd.value();
+        if line == 0 {
+            errlabel_upd.set_label("Select a row to update.");
+            return;
+        }
+        match check_upd(&editfield_upd.value()) {
+            Ok(text) => {
+                items_upd.borrow_mut()[(line - 1) as usize] = text;
+                refresh_list_browser(&mut browser_upd, &items_upd.borrow(), line);
+                errlabel_upd.set_label("");
+            }
+            Err(msg) => errlabel_upd.set_label(&msg),
+        }
+    });
+
+    let items_rmv = items.clone();
+    let mut browser_rmv = browser.clone();
+    remove.set_callback(move |_| {
+        let line = browser_rmv.value();
+        if line > 0 {
+            items_rmv.borrow_mut().remove((line - 1) as usize);
+            refresh_list_browser(&mut browser_rmv, &items_rmv.borrow(), line);
+        }
+    });
+
+    let items_up = items.clone();
+    let mut browser_up = browser.clone();
+    moveup.set_callback(move |_| {
+        let line = browser_up.value();
+        if line > 1 {
+            items_up.borrow_mut().swap((line - 1) as usize, (line - 2) as usize);
+            refresh_list_browser(&mut browser_up, &items_up.borrow(), line - 1);
+        }
+    });
+
+    let items_down = items.clone();
+    let mut browser_down = browser.clone();
+    movedown.set_callback(move |_| {
+        let line = browser_down.value();
+        if line > 0 && (line as usize) < items_down.borrow().len() {
+            items_down.borrow_mut().swap((line - 1) as usize, line as usize);
+            refresh_list_browser(&mut browser_down, &items_down.borrow(), line + 1);
+        }
+    });
+    // endregion
+
+    // region Do the Paste & Import callbacks.
+    // Both open the import preview.  The rows it accepts go on the end of the list.
+    let app_paste = *app;
+    let items_paste = items.clone();
+    let check_paste = check.clone();
+    let mut browser_paste = browser.clone();
+    paste.set_callback(move |_| {
+        if let Some(rows) = run_import_preview(&app_paste, "", check_paste.clone()) {
+            items_paste.borrow_mut().extend(rows);
+            let last = items_paste.borrow().len() as i32;
+            refresh_list_browser(&mut browser_paste, &items_paste.borrow(), last);
+        }
+    });
+
+    let app_import = *app;
+    let items_import = items.clone();
+    let check_import = check.clone();
+    let mut browser_import = browser.clone();
+    import.set_callback(move |_| {
+        let mut chooser = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+        chooser.set_title("Import a list");
+        chooser.set_filter("Lists\t*.{txt,csv,tsv}");
+        chooser.show();
+        let path = chooser.filename();
+        if path.as_os_str().is_empty() {
+            return;  // The user cancelled the file chooser.
+        }
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => {
+                dialog::alert_default(&format!("Could not read {}:\n{}", path.display(), err));
+                return;
+            }
+        };
+
+        if let Some(rows) = run_import_preview(&app_import, &text, check_import.clone()) {
+            items_import.borrow_mut().extend(rows);
+            let last = items_import.borrow().len() as i32;
+            refresh_list_browser(&mut browser_import, &items_import.borrow(), last);
+        }
+    });
+    // endregion
+
+    // region Do the Done & Cancel callbacks.
+    let items_done = items.clone();
+    let outcome_done = outcome.clone();
+    let mut win_done = win.clone();
+    done.set_callback(move |_| {
+        // Every row was checked when it went in, so parsing can only fail
+        // if `T`'s parser isn't consistent with itself.
+        let parsed: Result<Vec<T>, InputError> = items_done.borrow().iter()
+            .map(|text| text.parse::<T>().map_err(|_| InputError::ParseFailed(text.clone())))
+            .collect();
+        *outcome_done.borrow_mut() = Some(parsed);
+        win_done.hide();
+    });
+
+    let outcome_cancel = outcome.clone();
+    let mut win_cancel = win.clone();
+    cancel.set_callback(move |_| {
+        *outcome_cancel.borrow_mut() = Some(Err(InputError::Cancelled));
+        win_cancel.hide();
+    });
+
+    let outcome_win = outcome.clone();
+    win.set_callback(move |w| {
+        *outcome_win.borrow_mut() = Some(Err(InputError::Cancelled));
+        w.hide();
+    });
+    // endregion
+
+    while win.shown() {
+        if !app.wait() {
+            break;
+        }
+    }
+    win.hide();
+
+    let result = outcome.borrow_mut().take();
+    result.unwrap_or(Err(InputError::WindowClosed))
+}
+
+
+
+/// Uses FLTK's Input widget to prompt the user to enter String data.
+///
+/// `horiz` and `vert` are the size of the input window.  The prompt and
+/// the field sit in a 200 x 75 area in its center, so the window should be
+/// at least that big.
+/// -- For small input windows try 300 x 90 and adjust by trial and error.
+pub fn input_string(app: &App, prompt: &str, horiz: i32, vert: i32) -> String {
+
+    // region Set up the input window and input frame
+    let mut win = window::Window::default()
+        .with_size(horiz, vert)
+        .with_label("Input Window");
+    win.make_resizable(true);
+
+    let flex = group::Flex::default()
+        .with_size(200, 75)
+        .column()
+        .center_of_parent();
+
+    let _prompttext = frame::Frame::default().with_label(prompt);
+    // endregion
+
+    // region Set up the input widget inside the frame.
+    let mut input_widget = input::Input::default();
+    input_widget.set_trigger(CallbackTrigger::EnterKey);
+
+    // Set the input widget's callback.
+    let mut win2 = win.clone();
+    input_widget.set_callback(move |_| {
+        win2.hide();
+    });
+
+    flex.end();
+    win.end();
+    win.show();
+    // endregion
+
+    // region Deal with the input
+    while win.shown() {
+        app.wait();
+    }
+    input_widget.value()
+    // endregion
+}
+
+/// Shows the prompt window behind `input_char()`, `input_i64()` and
+/// `input_f64()`, set up from `opts`:  a prompt over an input widget of
+/// type `W` that closes when Enter is pressed.  Returns the field's text.
+fn run_plain_input_window<W>(app: &App, prompt: &str, opts: &InputOptions) -> String
+    where W: InputExt + WidgetBase + Default
+{
+    // region Set up the input window and input frame
+    let mut win = options_window(opts, (400, 100));
+
+    let flex = group::Flex::default()
+        .with_size(200, 75)
+        .column()
+        .center_of_parent();
+
+    let mut prompttext = frame::Frame::default().with_label(prompt);
+    style_prompt_label(&mut prompttext, opts);
+    // endregion
+
+    // region Set up the input widget inside the frame.
+    let mut input_widget = W::default();
+    style_input_widget(&mut input_widget, opts);
+    input_widget.set_trigger(CallbackTrigger::EnterKey);
+
+    // Set the input widget's callback.
+    let mut win2 = win.clone();
+    input_widget.set_callback(move |_| {
+        win2.hide();
+    });
+
+    flex.end();
+    win.end();
+    win.show();
+    // endregion
+
+    // region Deal with the input
+    while win.shown() {
+        app.wait();
+    }
+    input_widget.value()
+    // endregion
+}
+
+/// Uses FLTK's Input widget to prompt the user to enter character data.
+///
+pub fn input_char(app: &App, prompt: &str) -> char {
+    run_plain_input_window::<input::Input>(app, prompt, &InputOptions::default()).chars().next().unwrap()
+}
+
+/// Same as `input_char()`, set up from `opts`, but it never panics:  the
+/// window has OK and Cancel buttons, and an empty field, bad input or a
+/// cancelled window comes back as an `InputError`.  This is the same as
+/// `try_input_char_with()`.
+///
+pub fn input_char_with(app: &App, prompt: &str, opts: &InputOptions) -> Result<char, InputError> {
+    try_input_char_with(app, prompt, opts)
+}
+
+/// Uses FLTK's Input widget to prompt the user to enter i64 integer data.
+///
+pub fn input_i64(app: &App, prompt: &str) -> i64 {
+    run_plain_input_window::<input::IntInput>(app, prompt, &InputOptions::default()).trim().parse::<i64>().unwrap()
+}
+
+/// Same as `input_i64()`, set up from `opts`, but it never panics:  the
+/// window has OK and Cancel buttons, and an empty field, bad input or a
+/// cancelled window comes back as an `InputError`.  This is the same as
+/// `try_input_i64_with()`.
+///
+pub fn input_i64_with(app: &App, prompt: &str, opts: &InputOptions) -> Result<i64, InputError> {
+    try_input_i64_with(app, prompt, opts)
+}
+
+/// Uses FLTK's Input widget to prompt the user to enter f64 data.
+///
+pub fn input_f64(app: &App, prompt: &str) -> f64 {
+    run_plain_input_window::<input::FloatInput>(app, prompt, &InputOptions::default()).trim().parse::<f64>().unwrap()
+}
+
+/// Same as `input_f64()`, set up from `opts`, but it never panics:  the
+/// window has OK and Cancel buttons, and an empty field, bad input or a
+/// cancelled window comes back as an `InputError`.  This is the same as
+/// `try_input_f64_with()`.
+///
+pub fn input_f64_with(app: &App, prompt: &str, opts: &InputOptions) -> Result<f64, InputError> {
+    try_input_f64_with(app, prompt, opts)
+}
+
+/// Uses FLTK's Input widget to prompt the user to enter String data.
+/// Unlike `input_string()` this version has OK and Cancel buttons and
+/// reports an empty field or a cancelled window as an `InputError`.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = fltk::app::App::default();
+///
+///     match try_input_string(&app, "What is your name?") {
+///         Ok(name) => println!("\n Hello, {} \n", name),
+///         Err(InputError::Cancelled) => println!("\n Never mind. \n"),
+///         Err(err) => println!("\n Error:  {} \n", err),
+///     }
+///
+pub fn try_input_string(app: &App, prompt: &str) -> Result<String, InputError> {
+    try_input_string_with(app, prompt, &InputOptions::default())
+}
+
+/// Same as `try_input_string()`, set up from `opts`.
+///
+pub fn try_input_string_with(app: &App, prompt: &str, opts: &InputOptions) -> Result<String, InputError> {
+    let text = run_input_window::<input::Input, _, _>(app, prompt, opts, None, |text| Ok(text.to_string()))?;
+    if text.is_empty() {
+        return Err(InputError::Empty);
+    }
+    Ok(text)
+}
+
+/// Uses FLTK's Input widget to prompt the user to enter a single character.
+/// Returns `InputError::ParseFailed` if more than one character was entered.
+///
+pub fn try_input_char(app: &App, prompt: &str) -> Result<char, InputError> {
+    try_input_char_with(app, prompt, &InputOptions::default())
+}
+
+/// Same as `try_input_char()`, set up from `opts`.
+///
+pub fn try_input_char_with(app: &App, prompt: &str, opts: &InputOptions) -> Result<char, InputError> {
+    let filter: EditFilter = Box::new(|text: &str| {
+        if text.chars().count() > 1 { Err("Please enter just one character.".to_string()) } else { Ok(()) }
+    });
+    let text = run_input_window::<input::Input, _, _>(app, prompt, opts, Some(filter), |text| Ok(text.to_string()))?;
+    parse_input_text::<char>(&text)
+}
+
+/// Prompts the user for a single character as the user sees it -- one
+/// grapheme cluster -- so accented letters and emoji built from several
+/// `char`s count as one character.  The field won't take a second
+/// character as the user types.
+///
+/// If `allowed` isn't empty, only the graphemes in it are accepted by the
+/// field.  Returns the grapheme as a `String`, `InputError::Empty` if
+/// nothing was entered, or `InputError::Cancelled` if the user backs out.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = fltk::app::App::default();
+///
+///     let key = input_grapheme(&app, "Answer key for question 1?", &["A", "B", "C", "D", "E"]);
+///
+///     println!("\n key: {:?} \n", key);
+///
+pub fn input_grapheme(app: &App, prompt: &str, allowed: &[&str]) -> Result<String, InputError> {
+    input_grapheme_with(app, prompt, &InputOptions::default(), allowed)
+}
+
+/// Same as `input_grapheme()`, set up from `opts`.
+///
+pub fn input_grapheme_with(app: &App, prompt: &str, opts: &InputOptions,
+                           allowed: &[&str]) -> Result<String, InputError> {
+    let allowed: Vec<String> = allowed.iter().map(|g| g.to_string()).collect();
+    let filter: EditFilter = Box::new(move |text: &str| check_grapheme(text, &allowed));
+    let text = run_input_window::<input::Input, _, _>(app, prompt, opts, Some(filter), |text| Ok(text.to_string()))?;
+    if text.is_empty() {
+        return Err(InputError::Empty);
+    }
+    Ok(text)
+}
+
+/// Checks that `text` is at most one grapheme cluster, and that it is in
+/// `allowed` when `allowed` isn't empty.
+fn check_grapheme(text: &str, allowed: &[String]) -> Result<(), String> {
+    let mut graphemes = text.graphemes(true);
+    match (graphemes.next(), graphemes.next()) {
+        (None, _) => Ok(()),
+        (Some(_), Some(_)) => Err("Please enter just one character.".to_string()),
+        (Some(grapheme), None) => {
+            if allowed.is_empty() || allowed.iter().any(|a| a == grapheme) {
+                Ok(())
+            } else {
+                Err(format!("Please enter one of:  {}", allowed.join(", ")))
+            }
+        }
+    }
+}
+
+/// Uses FLTK's IntInput widget to prompt the user to enter i64 integer data.
+/// Never panics -- bad input is returned as an `InputError`.
+///
+pub fn try_input_i64(app: &App, prompt: &str) -> Result<i64, InputError> {
+    try_input_i64_with(app, prompt, &InputOptions::default())
+}
+
+/// Same as `try_input_i64()`, set up from `opts`.
+///
+pub fn try_input_i64_with(app: &App, prompt: &str, opts: &InputOptions) -> Result<i64, InputError> {
+    let text = run_input_window::<input::IntInput, _, _>(app, prompt, opts, None, |text| Ok(text.to_string()))?;
+    parse_input_text::<i64>(&text)
+}
+
+/// Uses FLTK's FloatInput widget to prompt the user to enter f64 data.
+/// Never panics -- bad input is returned as an `InputError`.
+///
+pub fn try_input_f64(app: &App, prompt: &str) -> Result<f64, InputError> {
+    try_input_f64_with(app, prompt, &InputOptions::default())
+}
+
+/// Same as `try_input_f64()`, set up from `opts`.
+///
+pub fn try_input_f64_with(app: &App, prompt: &str, opts: &InputOptions) -> Result<f64, InputError> {
+    let text = run_input_window::<input::FloatInput, _, _>(app, prompt, opts, None, |text| Ok(text.to_string()))?;
+    parse_input_text::<f64>(&text)
+}
+
+/// Prompts the user for a value of any type that implements `FromStr`.
+/// The text is trimmed and parsed when the user clicks OK.  If it doesn't
+/// parse, the parse error is shown in red under the field and the window
+/// stays open so the user can fix it.  Returns `InputError::Cancelled`
+/// if the user backs out.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = fltk::app::App::default();
+///
+///     let age: Result<u8, InputError> = input_parse(&app, "How old are you?");
+///     let addr = input_parse::<std::net::Ipv4Addr>(&app, "Server address?");
+///
+///     println!("\n age: {:?}   address: {:?} \n", age, addr);
+///
+pub fn input_parse<T>(app: &App, prompt: &str) -> Result<T, InputError>
+    where T: FromStr + 'static,
+          T::Err: fmt::Display
+{
+    input_parse_with(app, prompt, &InputOptions::default())
+}
+
+/// Same as `input_parse()`, set up from `opts`.
+///
+pub fn input_parse_with<T>(app: &App, prompt: &str, opts: &InputOptions) -> Result<T, InputError>
+    where T: FromStr + 'static,
+          T::Err: fmt::Display
+{
+    run_input_window::<input::Input, _, _>(app, prompt, opts, None, |text| parse_or_message::<T>(text))
+}
+
+/// Same as `input_parse()`, but the `rules` are checked as the user types.
+/// OK is greyed out and the first broken rule is shown under the field
+/// until the text passes every rule.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = fltk::app::App::default();
+///
+///     let rules = vec![
+///         Rule::NonEmpty,
+///         Rule::Min(1.0),
+///         Rule::Max(100.0),
+///         Rule::custom(|text| {
+///             if text.trim().ends_with('7') { Err("No sevens, please.".to_string()) } else { Ok(()) }
+///         }),
+///     ];
+///     let percent: Result<u32, InputError> = input_parse_with_rules(&app, "Percent correct?", &rules);
+///
+///     println!("\n percent: {:?} \n", percent);
+///
+pub fn input_parse_with_rules<T>(app: &App, prompt: &str, rules: &[Rule]) -> Result<T, InputError>
+    where T: FromStr + 'static,
+          T::Err: fmt::Display
+{
+    input_parse_with(app, prompt, &InputOptions::default().with_rules(rules))
+}
+
+/// Number types that `input_range()` can work with.
+///
+pub trait RangeValue: FromStr + PartialOrd + Copy + fmt::Display + 'static {
+    /// True for the floating point types.
+    const IS_FLOAT: bool;
+
+    /// Converts the value to an f64, e.g. for placing it on a slider.
+    fn to_f64(self) -> f64;
+
+    /// Converts an f64 back to this type, rounding for the integer types.
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! impl_range_value_int {
+    ($($t:ty),*) => {$(
+        impl RangeValue for $t {
+            const IS_FLOAT: bool = false;
+            fn to_f64(self) -> f64 { self as f64 }
+            fn from_f64(value: f64) -> Self { value.round() as $t }
+        }
+    )*};
+}
+
+macro_rules! impl_range_value_float {
+    ($($t:ty),*) => {$(
+        impl RangeValue for $t {
+            const IS_FLOAT: bool = true;
+            fn to_f64(self) -> f64 { self as f64 }
+            fn from_f64(value: f64) -> Self { value as $t }
+        }
+    )*};
+}
+
+impl_range_value_int!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize);
+impl_range_value_float!(f32, f64);
+
+/// Settings for `input_range()`.
+///
+#[derive(Clone)]
+pub struct RangeOptions<T: RangeValue> {
+    /// The lowest and highest values allowed.  Setting this also adds a
+    /// two-thumb slider under the fields.
+    pub bounds: Option<(T, T)>,
+    /// Both values must be a whole number of steps from the lower bound
+    /// (or from zero if there are no bounds).
+    pub step: Option<T>,
+    /// The most decimal places a floating point value may have.
+    pub decimals: Option<usize>,
+    /// The values in the fields when the window opens.
+    pub initial: Option<(T, T)>,
+}
+
+impl<T: RangeValue> Default for RangeOptions<T> {
+    fn default() -> Self {
+        RangeOptions { bounds: None, step: None, decimals: None, initial: None }
+    }
+}
+
+impl<T: RangeValue> RangeOptions<T> {
+    /// Sets the lowest and highest values allowed and turns on the slider.
+    pub fn with_bounds(mut self, lowest: T, highest: T) -> Self {
+        self.bounds = Some((lowest, highest));
+        self
+    }
+
+    /// Sets the step both values must land on.
+    pub fn with_step(mut self, step: T) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    /// Sets the most decimal places a floating point value may have.
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    /// Sets the values in the fields when the window opens.
+    pub fn with_initial(mut self, min: T, max: T) -> Self {
+        self.initial = Some((min, max));
+        self
+    }
+
+    /// Writes a value the way it should appear in the fields.  With no
+    /// decimal places set, a floating point value is rounded to the places
+    /// in the step, so a slider value such as 0.30000000000000004 shows as 0.3.
+    fn format(&self, value: T) -> String {
+        match (self.decimals, self.step) {
+            (Some(places), _) if T::IS_FLOAT => format!("{:.*}", places, value.to_f64()),
+            (None, Some(step)) if T::IS_FLOAT => {
+                let places = step.to_string().split_once('.').map_or(0, |(_, frac)| frac.len());
+                let scale = 10f64.powi(places as i32);
+                T::from_f64((value.to_f64() * scale).round() / scale).to_string()
+            }
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Checks the text of the minimum and maximum fields against `ropts`.
+/// Returns the two values, or a message explaining what is wrong.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::*;
+///
+///     let ropts = RangeOptions::default().with_step(5);
+///
+///     assert_eq!(check_range::<i64>("10", "25", &ropts), Ok((10, 25)));
+///     assert!(check_range::<i64>("25", "10", &ropts).is_err());
+///     assert!(check_range::<i64>("10", "12", &ropts).is_err());
+///
+pub fn check_range<T>(min_text: &str, max_text: &str, ropts: &RangeOptions<T>) -> Result<(T, T), String>
+    where T: RangeValue,
+          T::Err: fmt::Display
+{
+    let mut values = Vec::new();
+    for (name, text) in [("Minimum", min_text), ("Maximum", max_text)] {
+        let value = parse_or_message::<T>(text).map_err(|msg| format!("{}:  {}", name, msg))?;
+
+        if let (Some(places), true) = (ropts.decimals, T::IS_FLOAT) {
+            let used = text.trim().split_once('.').map_or(0, |(_, frac)| frac.len());
+            if used > places {
+                return Err(format!("{}:  use no more than {} decimal places.", name, places));
+            }
+        }
+
+        if let Some((lowest, highest)) = ropts.bounds {
+            if value < lowest || value > highest {
+                return Err(format!("{}:  must be between {} and {}.", name, lowest, highest));
+            }
+        }
+
+        if let Some(step) = ropts.step {
+            let base = ropts.bounds.map_or(0.0, |(lowest, _)| lowest.to_f64());
+            let steps = (value.to_f64() - base) / step.to_f64();
+            if (steps - steps.round()).abs() > 1e-9 {
+                return Err(format!("{}:  must be in steps of {}.", name, step));
+            }
+        }
+
+        values.push(value);
+    }
+
+    if values[0] > values[1] {
+        return Err("The minimum can't be more than the maximum.".to_string());
+    }
+    Ok((values[0], values[1]))
+}
+
+/// Prompts the user for a minimum and a maximum value and returns them as
+/// a `RangeInclusive`.  Works with the integer and floating point types.
+///
+/// The two fields are checked as the user types, and OK stays greyed out
+/// until the minimum is no more than the maximum and the step and decimal
+/// places in `ropts` are followed.  If `ropts` has bounds a two-thumb
+/// slider is shown under the fields, linked to them both ways.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = fltk::app::App::default();
+///
+///     let ropts = RangeOptions::default()
+///         .with_bounds(0.0, 100.0)
+///         .with_step(0.5)
+///         .with_decimals(1);
+///
+///     match input_range::<f64>(&app, "Range for the variable?", &ropts) {
+///         Ok(range) => println!("\n From {} to {} \n", range.start(), range.end()),
+///         Err(err) => println!("\n No range:  {} \n", err),
+///     }
+///
+pub fn input_range<T>(app: &App, prompt: &str, ropts: &RangeOptions<T>) -> Result<RangeInclusive<T>, InputError>
+    where T: RangeValue,
+          T::Err: fmt::Display
+{
+    input_range_with(app, prompt, &InputOptions::default(), ropts)
+}
+
+/// Same as `input_range()`, with the window set up from `opts`.
+/// The `initial` text and the `rules` in `opts` are not used.
+///
+pub fn input_range_with<T>(app: &App, prompt: &str, opts: &InputOptions,
+                           ropts: &RangeOptions<T>) -> Result<RangeInclusive<T>, InputError>
+    where T: RangeValue,
+          T::Err: fmt::Display
+{
+    // region Set up the window and the two fields.
+    let winheight = if ropts.bounds.is_some() { 230 } else { 190 };
+    let mut win = options_window(opts, (440, winheight));
+
+    let mut flex = group::Flex::default_fill().column();
+    flex.set_margin(10);
+    flex.set_pad(6);
+
+    let mut prompttext = frame::Frame::default().with_label(prompt);
+    style_prompt_label(&mut prompttext, opts);
+    flex.fixed(&prompttext, 30);
+
+    let fieldopts = InputOptions { initial: String::new(), ..opts.clone() };
+    let mut fieldrow = group::Flex::default().row();
+    let minlabel = frame::Frame::default().with_label("Minimum");
+    let mut minfield = input::Input::default();
+    style_input_widget(&mut minfield, &fieldopts);
+    let maxlabel = frame::Frame::default().with_label("Maximum");
+    let mut maxfield = input::Input::default();
+    style_input_widget(&mut maxfield, &fieldopts);
+    fieldrow.fixed(&minlabel, 70);
+    fieldrow.fixed(&maxlabel, 70);
+    fieldrow.end();
+    flex.fixed(&fieldrow, 30);
+
+    if let Some((min, max)) = ropts.initial {
+        minfield.set_value(&ropts.format(min));
+        maxfield.set_value(&ropts.format(max));
+    }
+
+    let slider = ropts.bounds.map(|(lowest, highest)| {
+        let mut slider = DualSlider::default();
+        slider.set_bounds(lowest.to_f64(), highest.to_f64());
+        slider.set_step(ropts.step.map_or(0.0, |step| step.to_f64()));
+        let (min, max) = ropts.initial.unwrap_or((lowest, highest));
+        slider.set_values(min.to_f64(), max.to_f64());
+        flex.fixed(&*slider, 30);
+        slider
+    });
+
+    let mut errlabel = frame::Frame::default();
+    errlabel.set_label_color(Color::Red);
+    errlabel.set_label_size(12);
+    flex.fixed(&errlabel, 20);
+    // endregion
+
+    // region Set up the OK & Cancel buttons.
+    let bttnrow = group::Flex::default().row();
+    let mut ok = button::ReturnButton::default().with_label("OK");
+    let mut cancel = button::Button::default().with_label("Cancel");
+    bttnrow.end();
+    flex.fixed(&bttnrow, 30);
+
+    flex.end();
+    win.end();
+    win.show();
+    // endregion
+
+    // region Check the fields as the user types, and keep the slider in step.
+    let ropts = Rc::new(ropts.clone());
+
+    let ropts_vld = ropts.clone();
+    let minfield_vld = minfield.clone();
+    let maxfield_vld = maxfield.clone();
+    let errlabel_vld = errlabel.clone();
+    let ok_vld = ok.clone();
+    let validate = Rc::new(move || -> Option<(T, T)> {
+        let mut errlabel = errlabel_vld.clone();
+        let mut ok = ok_vld.clone();
+        match check_range::<T>(&minfield_vld.value(), &maxfield_vld.value(), &ropts_vld) {
+            Ok(pair) => {
+                errlabel.set_label("");
+                ok.activate();
+                Some(pair)
+            }
+            Err(msg) => {
+                errlabel.set_label(&msg);
+                ok.deactivate();
+                None
+            }
+        }
+    });
+    validate();
+
+    for field in [&mut minfield, &mut maxfield] {
+        let validate_fld = validate.clone();
+        let slider_fld = slider.clone();
+        field.set_trigger(CallbackTrigger::Changed);
+        field.set_callback(move |_| {
+            if let (Some((min, max)), Some(mut slider)) = (validate_fld(), slider_fld.clone()) {
+                slider.set_values(min.to_f64(), max.to_f64());
+            }
+        });
+    }
+
+    if let Some(mut slider) = slider.clone() {
+        let ropts_sld = ropts.clone();
+        let mut minfield_sld = minfield.clone();
+        let mut maxfield_sld = maxfield.clone();
+        let validate_sld = validate.clone();
+        let state_sld = Rc::downgrade(&slider.state);   // A strong clone would keep the slider's state alive.
+        slider.set_callback(move |_| {
+            let Some(state) = state_sld.upgrade() else { return };
+            let (min, max) = {
+                let st = state.borrow();
+                (st.low, st.high)
+            };
+            minfield_sld.set_value(&ropts_sld.format(T::from_f64(min)));
+            maxfield_sld.set_value(&ropts_sld.format(T::from_f64(max)));
+            validate_sld();
+        });
+    }
+    // endregion
+
+    // region Do the button callbacks.
+    let outcome: Outcome<RangeInclusive<T>> = Rc::new(RefCell::new(None));
+
+    let outcome_ok = outcome.clone();
+    let validate_ok = validate.clone();
+    let mut win_ok = win.clone();
+    ok.set_callback(move |_| {
+        if let Some((min, max)) = validate_ok() {
+            *outcome_ok.borrow_mut() = Some(Ok(min..=max));
+            win_ok.hide();
+        }
+    });
+
+    let outcome_cancel = outcome.clone();
+    let mut win_cancel = win.clone();
+    cancel.set_callback(move |_| {
+        *outcome_cancel.borrow_mut() = Some(Err(InputError::Cancelled));
+        win_cancel.hide();
+    });
+
+    let outcome_win = outcome.clone();
+    win.set_callback(move |w| {
+        *outcome_win.borrow_mut() = Some(Err(InputError::Cancelled));
+        w.hide();
+    });
+    // endregion
+
+    while win.shown() {
+        if !app.wait() {
+            break;
+        }
+    }
+    win.hide();
+
+    let result = outcome.borrow_mut().take();
+    result.unwrap_or(Err(InputError::WindowClosed))
+}
+
+/// The values behind a `DualSlider`.
+#[derive(Debug, Clone, Copy)]
+struct DualSliderState {
+    lowest: f64,
+    highest: f64,
+    step: f64,
+    low: f64,
+    high: f64,
+    dragging: Option<bool>,   // Some(false) for the low thumb, Some(true) for the high thumb.
+}
+
+impl DualSliderState {
+    /// Clamps a value to the bounds and snaps it to the step.
+    fn snap(&self, value: f64) -> f64 {
+        let mut value = value.max(self.lowest).min(self.highest);
+        if self.step > 0.0 {
+            value = self.lowest + ((value - self.lowest) / self.step).round() * self.step;
+            value = value.min(self.highest);
+        }
+        value
+    }
+}
+
+/// A horizontal slider with two thumbs, for picking a low and a high value.
+/// The low thumb can't pass the high thumb.  The slider's callback runs
+/// whenever a thumb is moved.
+///
+#[derive(Clone)]
+pub struct DualSlider {
+    inner: widget::Widget,
+    state: Rc<RefCell<DualSliderState>>,
+}
+
+fltk::widget_extends!(DualSlider, widget::Widget, inner);
+
+impl Default for DualSlider {
+    fn default() -> Self {
+        DualSlider::new(0, 0, 0, 0)
+    }
+}
+
+impl DualSlider {
+    const THUMB_W: i32 = 12;
+
+    /// Creates a slider running from 0 to 100 with both thumbs at the ends.
+    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        let mut inner = widget::Widget::new(x, y, w, h, None);
+        let state = Rc::new(RefCell::new(DualSliderState {
+            lowest: 0.0, highest: 100.0, step: 0.0, low: 0.0, high: 100.0, dragging: None,
+        }));
+
+        // region Draw the track, the span between the thumbs, and the thumbs.
+        let state_draw = state.clone();
+        inner.draw(move |w| {
+            let st = *state_draw.borrow();
+            let track_y = w.y() + w.h() / 2 - 3;
+            let low_x = DualSlider::pixel_for(w, &st, st.low);
+            let high_x = DualSlider::pixel_for(w, &st, st.high);
+
+            draw::draw_box(FrameType::DownBox, w.x() + DualSlider::THUMB_W / 2, track_y,
+                           w.w() - DualSlider::THUMB_W, 6, Color::Background2);
+            draw::draw_rect_fill(low_x, track_y + 1, high_x - low_x, 4, Color::Selection);
+            for thumb_x in [low_x, high_x] {
+                draw::draw_box(FrameType::UpBox, thumb_x - DualSlider::THUMB_W / 2, w.y() + 2,
+                               DualSlider::THUMB_W, w.h() - 4, Color::Background);
+            }
+        });
+        // endregion
+
+        // region Move the nearest thumb with the mouse.
+        let state_hndl = state.clone();
+        inner.handle(move |w, ev| match ev {
+            Event::Push | Event::Drag => {
+                {
+                    let mut st = state_hndl.borrow_mut();
+                    let value = DualSlider::value_for(w, &st, app::event_x());
+                    if ev == Event::Push {
+                        let nearer_high = (value - st.high).abs() < (value - st.low).abs()
+                            || (st.low == st.high && value > st.high);
+                        st.dragging = Some(nearer_high);
+                    }
+                    match st.dragging {
+                        Some(true) => st.high = st.snap(value).max(st.low),
+                        Some(false) => st.low = st.snap(value).min(st.high),
+                        None => {}
+                    }
+                }
+                w.redraw();
+                w.do_callback();
+                true
+            }
+            Event::Released => {
+                state_hndl.borrow_mut().dragging = None;
+                true
+            }
+            _ => false,
+        });
+        // endregion
+
+        DualSlider { inner, state }
+    }
+
+    /// Sets the lowest and highest values the slider can reach.
+    pub fn set_bounds(&mut self, lowest: f64, highest: f64) {
+        let mut st = self.state.borrow_mut();
+        st.lowest = lowest;
+        st.highest = highest;
+        st.low = st.snap(st.low);
+        st.high = st.snap(st.high);
+        drop(st);
+        self.inner.redraw();
+    }
+
+    /// Sets the step the thumbs snap to.  Zero means no snapping.
+    pub fn set_step(&mut self, step: f64) {
+        self.state.borrow_mut().step = step;
+    }
+
+    /// Moves the thumbs to `low` and `high`.
+    pub fn set_values(&mut self, low: f64, high: f64) {
+        let mut st = self.state.borrow_mut();
+        st.low = st.snap(low.min(high));
+        st.high = st.snap(high.max(low));
+        drop(st);
+        self.inner.redraw();
+    }
+
+    /// Returns the values of the low and high thumbs.
+    pub fn values(&self) -> (f64, f64) {
+        let st = self.state.borrow();
+        (st.low, st.high)
+    }
+
+    /// The x pixel of the center of a thumb at `value`.
+    fn pixel_for(w: &widget::Widget, st: &DualSliderState, value: f64) -> i32 {
+        let span = (st.highest - st.lowest).max(f64::EPSILON);
+        let usable = (w.w() - DualSlider::THUMB_W) as f64;
+        w.x() + DualSlider::THUMB_W / 2 + ((value - st.lowest) / span * usable) as i32
+    }
+
+    /// The value under the x pixel `px`.
+    fn value_for(w: &widget::Widget, st: &DualSliderState, px: i32) -> f64 {
+        let usable = (w.w() - DualSlider::THUMB_W).max(1) as f64;
+        let frac = (px - w.x() - DualSlider::THUMB_W / 2) as f64 / usable;
+        st.lowest + frac.clamp(0.0, 1.0) * (st.highest - st.lowest)
+    }
+}
+
+/// The thousands separator and decimal mark used to show and read numbers.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::NumberFormat;
+///
+///     let us = NumberFormat::us();
+///     assert_eq!(us.format_f64(1234567.891, Some(2)), "1,234,567.89");
+///     assert_eq!(us.parse_f64("1,234,567.89"), Some(1234567.89));
+///
+///     let de = NumberFormat::european();
+///     assert_eq!(de.format_i64(-1234567), "-1.234.567");
+///     assert_eq!(de.parse_i64("1.234.567"), Some(1234567));
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberFormat {
+    /// Goes between each group of three digits.
+    pub thousands: char,
+    /// Goes between the whole number part and the fraction.
+    pub decimal: char,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat::us()
+    }
+}
+
+impl NumberFormat {
+    /// Creates a format from a thousands separator and a decimal mark.
+    pub fn new(thousands: char, decimal: char) -> Self {
+        NumberFormat { thousands, decimal }
+    }
+
+    /// 1,234,567.89
+    pub fn us() -> Self {
+        NumberFormat::new(',', '.')
+    }
+
+    /// 1.234.567,89
+    pub fn european() -> Self {
+        NumberFormat::new('.', ',')
+    }
+
+    /// 1 234 567,89
+    pub fn french() -> Self {
+        NumberFormat::new(' ', ',')
+    }
+
+    /// 1'234'567.89
+    pub fn swiss() -> Self {
+        NumberFormat::new('\'', '.')
+    }
+
+    /// Writes an integer with thousands separators.
+    pub fn format_i64(&self, num: i64) -> String {
+        let sign = if num < 0 { "-" } else { "" };
+        format!("{}{}", sign, self.group_digits(&num.unsigned_abs().to_string()))
+    }
+
+    /// Writes a number with thousands separators.  If `decimals` is given
+    /// the number is rounded to that many places.
+    pub fn format_f64(&self, num: f64, decimals: Option<usize>) -> String {
+        let plain = match decimals {
+            Some(places) => format!("{:.*}", places, num.abs()),
+            None => num.abs().to_string(),
+        };
+        let sign = if num < 0.0 && plain.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+        match plain.split_once('.') {
+            Some((whole, frac)) => format!("{}{}{}{}", sign, self.group_digits(whole), self.decimal, frac),
+            None => format!("{}{}", sign, self.group_digits(&plain)),
+        }
+    }
+
+    /// Reads an integer written with or without thousands separators.
+    /// Returns `None` if the separators are in the wrong places.
+    pub fn parse_i64(&self, text: &str) -> Option<i64> {
+        let plain = self.plain_text(text)?;
+        if plain.contains('.') {
+            return None;
+        }
+        plain.parse::<i64>().ok()
+    }
 
-        let bttn_width = label_len + BTTN_LABEL_PADDING * 2;
-        let bttn_height = label_height + BTTN_LABEL_PADDING * 2;
+    /// Reads a number written with or without thousands separators.
+    /// Returns `None` if the separators are in the wrong places.
+    pub fn parse_f64(&self, text: &str) -> Option<f64> {
+        self.plain_text(text)?.parse::<f64>().ok()
+    }
 
-        win.hide();
-        (bttn_width, bttn_height)
+    /// Puts the thousands separator between each group of three digits.
+    fn group_digits(&self, digits: &str) -> String {
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(self.thousands);
+            }
+            grouped.push(digit);
+        }
+        grouped
     }
 
-    /// Returns the coordinates for positioning a button centered relative to
-    /// a window.  I.e. (0,0) is the top left corner of the window.  The coordinates
-    /// returned by this function are for the top, left positon of the centered button.
-    pub fn fltk_center_button_in_win(win: &Window, bttn: &Button) -> (i32, i32) {
+    /// Turns formatted text into the plain form Rust's parsers read,
+    /// checking that the separators group the digits in threes.
+    fn plain_text(&self, text: &str) -> Option<String> {
+        let text = text.trim();
+        let (sign, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (whole, frac) = match unsigned.split_once(self.decimal) {
+            Some((whole, frac)) => (whole, Some(frac)),
+            None => (unsigned, None),
+        };
 
-        // Find coordinates to center the button both vertically and horizontally
-        let (centerx, centery) = fltk_find_center_wndw(&win);
-        let xxx = centerx - (bttn.w() / 2);
-        let yyy = centery - (bttn.h() / 2);
+        // A space separator also matches the no-break spaces used in print.
+        let is_sep = |c: char| c == self.thousands
+            || (self.thousands == ' ' && (c == '\u{a0}' || c == '\u{202f}'));
+        let groups: Vec<&str> = whole.split(is_sep).collect();
+        if groups.len() > 1 {
+            let first_ok = (1..=3).contains(&groups[0].len());
+            let rest_ok = groups[1..].iter().all(|g| g.len() == 3);
+            if !first_ok || !rest_ok {
+                return None;
+            }
+        }
 
-        (xxx, yyy)
+        let mut plain = format!("{}{}", sign, groups.concat());
+        if let Some(frac) = frac {
+            plain.push('.');
+            plain.push_str(frac);
+        }
+        if plain.chars().any(|c| !(c.is_ascii_digit() || c == '.' || c == '-')) {
+            return None;
+        }
+        Some(plain)
     }
+}
 
+/// A numeric input field that accepts numbers written with thousands
+/// separators, such as "1,234,567.89", and reformats its value when it
+/// loses focus.
+///
+/// FLTK's `IntInput` and `FloatInput` won't let the separators be typed,
+/// so this is built on a plain `Input` that filters the keys itself in the
+/// same way -- only digits, a sign, the separators of its `NumberFormat`
+/// and (for decimals) the decimal mark get through.
+///
+/// Example:
+///
+///     use fltk::{prelude::*, *};
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = app::App::default();
+///     let mut win = window::Window::default().with_size(300, 130);
+///
+///     let mut amount = NumberInput::new(100, 20, 150, 30, NumberFormat::us(), false)
+///         .with_decimals(2);
+///     amount.set_number(1234567.891);     // Shows 1,234,567.89
+///     let mut total = NumberOutput::new(100, 70, 150, 30, NumberFormat::us());
+///
+///     win.end();
+///     win.show();
+///
+///     amount.set_trigger(enums::CallbackTrigger::Changed);
+///     let amount_cb = amount.clone();
+///     amount.set_callback(move |_| {
+///         if let Some(num) = amount_cb.number() {
+///             total.set_number(num * 2.0, Some(2));
+///         }
+///     });
+///
+///     app.run().unwrap();
+///
+#[derive(Clone)]
+pub struct NumberInput {
+    inner: input::Input,
+    fmt: NumberFormat,
+    integer: bool,
+    decimals: Rc<RefCell<Option<usize>>>,
 }
 
-/// Input functions for FLTK-RS using the `fltk::input` module.
-///  
-pub mod input_fltk {
+fltk::widget_extends!(NumberInput, input::Input, inner);
 
-/*
-// todo: Still getting "unused" warnings.  Fix it if you can figure out how.
+impl NumberInput {
+    /// Creates the field.  Set `integer` to take whole numbers only.
+    pub fn new(x: i32, y: i32, w: i32, h: i32, fmt: NumberFormat, integer: bool) -> Self {
+        let mut inner = input::Input::new(x, y, w, h, None);
+        let decimals: Rc<RefCell<Option<usize>>> = Rc::new(RefCell::new(None));
 
-*/  // TODO's
+        let decimals_hndl = decimals.clone();
+        inner.handle(move |i, ev| match ev {
+            // Turn down any typed or pasted character a number can't have.
+            Event::KeyDown | Event::Paste => {
+                if app::is_event_ctrl() {
+                    return false;
+                }
+                if number_text_allowed(&app::event_text(), fmt, integer, ev == Event::Paste) {
+                    false
+                } else {
+                    dialog::beep(dialog::BeepType::Error);
+                    true
+                }
+            }
+            Event::Unfocus => {
+                if let Some(text) = reformat_number_text(&i.value(), fmt, integer, *decimals_hndl.borrow()) {
+                    i.set_value(&text);
+                }
+                false
+            }
+            _ => false,
+        });
 
-use fltk::app::App;
-use fltk::{frame, group, input, window};
-use fltk::enums::{CallbackTrigger};
-use fltk::prelude::{GroupExt, InputExt, WidgetExt, WindowExt};
+        NumberInput { inner, fmt, integer, decimals }
+    }
 
-    /// Allows the user to input a vector of Strings.
-///
-pub fn input_strvec(app: &App, prompt: &str, horiz: i32, vert: i32) -> Vec<String> {
-    let mut list = Vec::new();
-    let mut i = input_i64(app, "How many items in your list?");
+    /// Rounds the shown value to this many decimal places when it is reformatted.
+    pub fn with_decimals(self, decimals: usize) -> Self {
+        *self.decimals.borrow_mut() = Some(decimals);
+        self
+    }
 
-    while app.wait() && i > 0 {
-        let newelem = input_string(app, prompt, horiz, vert);
-        list.push(newelem);
-        i -= 1;
+    /// Returns the value of the field, or `None` if it isn't a number.
+    pub fn number(&self) -> Option<f64> {
+        self.fmt.parse_f64(&self.inner.value())
     }
 
-    list
-}
+    /// Returns the value of the field as an integer, or `None` if it isn't
+    /// a whole number.
+    pub fn number_i64(&self) -> Option<i64> {
+        self.fmt.parse_i64(&self.inner.value())
+    }
 
-/// Allows the user to input a vector of f64 integers.
-///
-pub fn input_f64vec(app: &App, prompt: &str) -> Vec<f64> {
-    let mut list = Vec::new();
-    let mut i = input_i64(app, "How many items in your list?");
+    /// Shows `num` in the field with thousands separators.
+    pub fn set_number(&mut self, num: f64) {
+        let text = if self.integer {
+            self.fmt.format_i64(num.round() as i64)
+        } else {
+            self.fmt.format_f64(num, *self.decimals.borrow())
+        };
+        self.inner.set_value(&text);
+    }
 
+    /// Rewrites the value with the separators in the right places.
+    /// This happens on its own when the field loses focus.
+    pub fn reformat(&mut self) {
+        if let Some(text) = reformat_number_text(&self.inner.value(), self.fmt, self.integer, *self.decimals.borrow()) {
+            self.inner.set_value(&text);
+        }
+    }
+}
 
+/// Whether a `NumberInput` should take typed or pasted `text`.  Editing
+/// keys such as BackSpace, Delete, Tab, Enter and Escape come through as
+/// control characters and are always let through.
+fn number_text_allowed(text: &str, fmt: NumberFormat, integer: bool, pasted: bool) -> bool {
+    let allowed = |c: char| c.is_ascii_digit() || c == '-' || c == '+'
+        || c == fmt.thousands || (!integer && c == fmt.decimal)
+        || (pasted && c.is_whitespace())
+        || (!pasted && c.is_control());
+    text.chars().all(allowed)
+}
 
-    while app.wait() && i > 0 {
-        let newelem = input_f64(app, prompt);
-        list.push(newelem);
-        i -= 1;
+/// Rewrites the text of a `NumberInput`.  Returns `None` if the text
+/// isn't a number, so the user's text can be left alone.
+fn reformat_number_text(text: &str, fmt: NumberFormat, integer: bool, decimals: Option<usize>) -> Option<String> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    if integer {
+        fmt.parse_i64(text).map(|num| fmt.format_i64(num))
+    } else {
+        fmt.parse_f64(text).map(|num| {
+            // Keep the user's decimal places unless a number of places was set.
+            let places = decimals.or_else(|| text.trim().split_once(fmt.decimal).map(|(_, frac)| frac.len()));
+            fmt.format_f64(num, places)
+        })
     }
-    list
 }
 
-/// Allows the user to input a vector of characters.
+/// A read-only field that shows numbers with thousands separators.
+/// The partner of `NumberInput`.
 ///
-pub fn input_charvec(app: &App, prompt: &str) -> Vec<char> {
-    let mut list = Vec::new();
-    let mut i = input_i64(app, "How many items in your list?");
+#[derive(Clone)]
+pub struct NumberOutput {
+    inner: output::Output,
+    fmt: NumberFormat,
+}
+
+fltk::widget_extends!(NumberOutput, output::Output, inner);
 
-    while app.wait() && i > 0 {
-        let newelem = input_char(app, prompt);
-        list.push(newelem);
-        i -= 1;
+impl NumberOutput {
+    /// Creates the field.
+    pub fn new(x: i32, y: i32, w: i32, h: i32, fmt: NumberFormat) -> Self {
+        let inner = output::Output::new(x, y, w, h, None);
+        NumberOutput { inner, fmt }
     }
-    list
-}
 
-/// Allows the user to input a vector of i64 integers.
-///
-pub fn input_i64vec(app: &App, prompt: &str) -> Vec<i64> {
-    let mut list = Vec::new();
-    let mut i = input_i64(app, "How many items in your list?");
+    /// Shows `num`, rounded to `decimals` places if given.
+    pub fn set_number(&mut self, num: f64, decimals: Option<usize>) {
+        self.inner.set_value(&self.fmt.format_f64(num, decimals));
+    }
 
-    while app.wait() && i > 0 {
-        let newelem = input_i64(app, prompt);
-        list.push(newelem);
-        i -= 1;
+    /// Shows the whole number `num`.
+    pub fn set_int(&mut self, num: i64) {
+        self.inner.set_value(&self.fmt.format_i64(num));
     }
-    list
 }
 
+/// Trims and parses `text`, turning any failure into a message
+/// that can be shown to the user.
+fn parse_or_message<T>(text: &str) -> Result<T, String>
+    where T: FromStr,
+          T::Err: fmt::Display
+{
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err("Please enter a value.".to_string());
+    }
+    trimmed.parse::<T>().map_err(|err| err.to_string())
+}
 
+/// Checks the whole text of a field after each edit.  Returning `Err`
+/// undoes the edit and shows the message.
+type EditFilter = Box<dyn Fn(&str) -> Result<(), String>>;
 
-/// Uses FLTK's Input widget to prompt the user to enter String data.
-///
-/// Works best if you set the horiz and vert to values 10 pixels less than the size
-///      of the main window with the flex size set to 10 pixels less than that.
-/// -- For large input windows try 790 x 490 first.
-/// -- For small input windows try 300 x 90 and adjust by trial and error.
-pub fn input_string(app: &App, prompt: &str, horiz: i32, vert: i32) -> String {
+/// Checks one item of a list prompt, returning the text to keep or the
+/// reason it fails.
+type ItemCheck = Rc<dyn Fn(&str) -> Result<String, String>>;
 
-    // region Set up the input window and input frame
-    let mut win = window::Window::default()
-        .with_size(horiz, vert)
-        .with_label("Input Window");
-    win.make_resizable(true);
+/// Where a prompt window's callbacks leave the result for the caller.
+type Outcome<T> = Rc<RefCell<Option<Result<T, InputError>>>>;
 
-    let flex = group::Flex::default()
-        .with_size(200, 75)
-        .column()
-        .center_of_parent();
+/// Converts the text from a prompt into a `T`, mapping the failures
+/// onto `InputError::Empty` and `InputError::ParseFailed`.
+fn parse_input_text<T: FromStr>(text: &str) -> Result<T, InputError> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err(InputError::Empty);
+    }
+    trimmed.parse::<T>().map_err(|_| InputError::ParseFailed(text.to_string()))
+}
 
-    let _prompttext = frame::Frame::default().with_label(prompt);
+/// Shows a prompt window containing an input widget of type `W` along
+/// with OK and Cancel buttons.  When the user clicks OK or presses Enter
+/// the text is handed to `accept`.  If `accept` returns an error message
+/// it is shown in red under the field and the window stays open.
+/// The rules in `opts` are checked as the user types; while any of them
+/// fails the message is shown and OK is greyed out.  If there is a
+/// `filter`, any edit it turns down is undone on the spot and its message
+/// is shown instead.
+/// Escape, Cancel and the title bar close button all return
+/// `InputError::Cancelled`.
+fn run_input_window<W, T, F>(app: &App, prompt: &str, opts: &InputOptions,
+                             filter: Option<EditFilter>, mut accept: F) -> Result<T, InputError>
+    where W: InputExt + WidgetBase + Default + Clone + 'static,
+          T: 'static,
+          F: FnMut(&str) -> Result<T, String> + 'static
+{
+    // region Set up the input window and input frame
+    let mut win = options_window(opts, (400, 150));
+
+    // Margins rather than a size taken off the window's, which a small window would make negative.
+    let mut flex = group::Flex::default_fill().column();
+    flex.set_margins(50, 10, 50, 10);
+
+    let mut prompttext = frame::Frame::default().with_label(prompt);
+    style_prompt_label(&mut prompttext, opts);
+    let mut input_widget = W::default();
+    style_input_widget(&mut input_widget, opts);
+
+    // The error label stays empty until the text fails to parse or breaks a rule.
+    let mut errlabel = frame::Frame::default();
+    errlabel.set_label_color(Color::Red);
+    errlabel.set_label_size(12);
+    flex.fixed(&errlabel, 20);
     // endregion
 
-    // region Set up the input widget inside the frame.
-    let mut input_widget = input::Input::default();
-    input_widget.set_trigger(CallbackTrigger::EnterKey);
-
-    // Set the input widget's callback.
-    let mut win2 = win.clone();
-    input_widget.set_callback(move |_| {
-        win2.hide();
-    });
+    // region Set up the OK & Cancel buttons.
+    // The input widget keeps its default trigger, so the Enter key
+    // falls through to the ReturnButton.
+    let bttnrow = group::Flex::default().row();
+    let mut ok = button::ReturnButton::default().with_label("OK");
+    let mut cancel = button::Button::default().with_label("Cancel");
+    bttnrow.end();
+    flex.fixed(&bttnrow, 30);
 
     flex.end();
     win.end();
     win.show();
     // endregion
 
-    // region Deal with the input
-    while win.shown() {
-        app.wait();
+    // region Check the filter & the rules as the user types.
+    let rules = Rc::new(opts.rules.clone());
+    if !rules.is_empty() || filter.is_some() {
+        let rules_chng = rules.clone();
+        let mut errlabel_chng = errlabel.clone();
+        let mut ok_chng = ok.clone();
+        let mut show_violation = move |text: &str| {
+            match check_rules(&rules_chng, text) {
+                Ok(()) => {
+                    errlabel_chng.set_label("");
+                    ok_chng.activate();
+                }
+                Err(msg) => {
+                    errlabel_chng.set_label(&msg);
+                    ok_chng.deactivate();
+                }
+            }
+        };
+        show_violation(&input_widget.value());
+
+        // Edits the filter turns down are undone before the rules see them.
+        let mut lastgood = input_widget.value();
+        let mut errlabel_fltr = errlabel.clone();
+        input_widget.set_trigger(CallbackTrigger::Changed);
+        input_widget.set_callback(move |i| {
+            if let Some(filter) = &filter {
+                if let Err(msg) = filter(&i.value()) {
+                    let pos = i.position();
+                    i.set_value(&lastgood);
+                    let _ = i.set_position(pos.min(lastgood.len() as i32));
+                    errlabel_fltr.set_label(&msg);
+                    dialog::beep(dialog::BeepType::Error);
+                    return;
+                }
+            }
+            lastgood = i.value();
+            show_violation(&lastgood);
+        });
     }
-    input_widget.value()
-    // endregion
-}
-
-/// Uses FLTK's Input widget to prompt the user to enter character data.
-///
-pub fn input_char(app: &App, prompt: &str) -> char {
-
-    // region Set up the input window and input frame
-    let mut win = window::Window::default()
-        .with_size(400, 100)
-        .with_label("Input Window");
-    win.make_resizable(true);
-
-    let flex = group::Flex::default()
-        .with_size(200, 75)
-        .column()
-        .center_of_parent();
-
-    let _prompttext = frame::Frame::default().with_label(prompt);
     // endregion
 
-    // region Set up the input widget inside the frame.
-    let mut input_widget = input::Input::default();
-    input_widget.set_trigger(CallbackTrigger::EnterKey);
+    // region Do the callbacks.
+    let outcome: Outcome<T> = Rc::new(RefCell::new(None));
+
+    let outcome_ok = outcome.clone();
+    let mut input_ok = input_widget.clone();
+    let mut win_ok = win.clone();
+    ok.set_callback(move |_| {
+        let text = input_ok.value();
+        let checked = check_rules(&rules, &text).and_then(|_| accept(&text));
+        match checked {
+            Ok(value) => {
+                *outcome_ok.borrow_mut() = Some(Ok(value));
+                win_ok.hide();
+            }
+            Err(msg) => {
+                errlabel.set_label(&msg);
+                let _ = input_ok.take_focus();
+                win_ok.redraw();
+            }
+        }
+    });
 
-    // Set the input widget's callback.
-    let mut win2 = win.clone();
-    input_widget.set_callback(move |_| {
-        win2.hide();
+    let outcome_cancel = outcome.clone();
+    let mut win_cancel = win.clone();
+    cancel.set_callback(move |_| {
+        *outcome_cancel.borrow_mut() = Some(Err(InputError::Cancelled));
+        win_cancel.hide();
     });
 
-    flex.end();
-    win.end();
-    win.show();
+    // Both the Escape key and the title bar close button run the window's callback.
+    let outcome_win = outcome.clone();
+    win.set_callback(move |w| {
+        *outcome_win.borrow_mut() = Some(Err(InputError::Cancelled));
+        w.hide();
+    });
     // endregion
 
     // region Deal with the input
     while win.shown() {
-        app.wait();
+        if !app.wait() {
+            break;
+        }
     }
-    input_widget.value().chars().nth(0).unwrap()
+    win.hide();
 
+    let result = outcome.borrow_mut().take();
+    result.unwrap_or(Err(InputError::WindowClosed))
     // endregion
 }
 
-/// Uses FLTK's Input widget to prompt the user to enter i64 integer data.
+/// Uses FLTK's MultilineInput widget to prompt the user for several
+/// lines or paragraphs of text.
 ///
-pub fn input_i64(app: &App, prompt: &str) -> i64 {
+/// Enter starts a new line.  Click OK or press Ctrl+Enter to submit.
+/// Long lines wrap at the edge of the field, and a counter under the field
+/// shows the number of characters and lines.  If `max_len` is given the
+/// field won't take more than that many characters.
+/// Returns `InputError::Empty` for an empty field and
+/// `InputError::Cancelled` if the user backs out.
+///
+/// `horiz` and `vert` are the size of the prompt window.  The field
+/// grows with the window, so a bigger window gives more room to type.
+///
+/// Example:
+///
+///     use lib_myfltk::input_fltk::*;
+///
+///     let app = fltk::app::App::default();
+///
+///     match input_str_large(&app, "Enter the question stem.", 790, 490, Some(2000)) {
+///         Ok(stem) => println!("\n The stem is:\n{} \n", stem),
+///         Err(err) => println!("\n No stem:  {} \n", err),
+///     }
+///
+pub fn input_str_large(app: &App, prompt: &str, horiz: i32, vert: i32,
+                       max_len: Option<usize>) -> Result<String, InputError> {
+    let opts = InputOptions::default().with_size(horiz, vert);
+    input_str_large_with(app, prompt, &opts, max_len)
+}
+
+/// Same as `input_str_large()`, set up from `opts`.
+///
+pub fn input_str_large_with(app: &App, prompt: &str, opts: &InputOptions,
+                            max_len: Option<usize>) -> Result<String, InputError> {
 
     // region Set up the input window and input frame
-    let mut win = window::Window::default()
-        .with_size(400, 100)
-        .with_label("Input Window");
-    win.make_resizable(true);
+    let mut win = options_window(opts, (600, 400));
 
-    let flex = group::Flex::default()
-        .with_size(200, 75)
-        .column()
-        .center_of_parent();
+    let mut flex = group::Flex::default_fill().column();
+    flex.set_margin(10);
+    flex.set_pad(6);
 
-    let _prompttext = frame::Frame::default().with_label(prompt);
-    // endregion
+    let mut prompttext = frame::Frame::default().with_label(prompt);
+    style_prompt_label(&mut prompttext, opts);
+    flex.fixed(&prompttext, 30);
 
-    // region Set up the input widget inside the frame.
-    let mut input_widget = input::IntInput::default();
-    input_widget.set_trigger(CallbackTrigger::EnterKey);
+    let mut input_widget = input::MultilineInput::default();
+    style_input_widget(&mut input_widget, opts);
+    input_widget.set_wrap(true);
+    if let Some(len) = max_len {
+        input_widget.set_maximum_size(len as i32);
+    }
 
-    // Set the input widget's callback.
-    let mut win2 = win.clone();
-    input_widget.set_callback(move |_| {
-        win2.hide();
-    });
+    let mut counter = frame::Frame::default().with_align(Align::Inside | Align::Right);
+    counter.set_label_size(12);
+    flex.fixed(&counter, 20);
+
+    let mut errlabel = frame::Frame::default();
+    errlabel.set_label_color(Color::Red);
+    errlabel.set_label_size(12);
+    flex.fixed(&errlabel, 20);
+    // endregion
+
+    // region Set up the OK & Cancel buttons.
+    let bttnrow = group::Flex::default().row();
+    let _spacer = frame::Frame::default();
+    let mut ok = button::Button::default().with_label("OK  (Ctrl+Enter)");
+    let mut cancel = button::Button::default().with_label("Cancel");
+    bttnrow.end();
+    flex.fixed(&bttnrow, 30);
 
     flex.end();
     win.end();
     win.show();
-
+    let _ = input_widget.take_focus();
     // endregion
 
-    // region Deal with the input
-    while win.shown() {
-        app.wait();
+    // region Update the counter & check the rules as the user types.
+    let mut rules = opts.rules.clone();
+    if let Some(len) = max_len {
+        rules.push(Rule::MaxLen(len));
     }
-    input_widget.value().trim().parse::<i64>().unwrap()
+    let rules = Rc::new(rules);
+
+    let rules_chng = rules.clone();
+    let mut errlabel_chng = errlabel.clone();
+    let mut ok_chng = ok.clone();
+    let mut update_counter = move |text: &str| {
+        let chars = text.chars().count();
+        let lines = if text.is_empty() { 0 } else { text.lines().count() + text.ends_with('\n') as usize };
+        let charcount = match max_len {
+            Some(len) => format!("{} / {}", chars, len),
+            None => chars.to_string(),
+        };
+        counter.set_label(&format!("{} characters, {} lines", charcount, lines));
 
+        match check_rules(&rules_chng, text) {
+            Ok(()) => {
+                errlabel_chng.set_label("");
+                ok_chng.activate();
+            }
+            Err(msg) => {
+                errlabel_chng.set_label(&msg);
+                ok_chng.deactivate();
+            }
+        }
+    };
+    update_counter(&input_widget.value());
+    input_widget.set_trigger(CallbackTrigger::Changed);
+    input_widget.set_callback(move |i| update_counter(&i.value()));
+
+    // Ctrl+Enter submits.  A plain Enter is left to the widget, which starts a new line.
+    let mut ok_key = ok.clone();
+    input_widget.handle(move |_, ev| {
+        if ev == Event::KeyDown
+            && app::is_event_ctrl()
+            && (app::event_key() == Key::Enter || app::event_key() == Key::KPEnter) {
+            if ok_key.active() {
+                ok_key.do_callback();
+            }
+            return true;
+        }
+        false
+    });
     // endregion
-}
-
-/// Uses FLTK's Input widget to prompt the user to enter f64 data.
-///
-pub fn input_f64(app: &App, prompt: &str) -> f64 {
 
-    // region Set up the input window and input frame
-    let mut win = window::Window::default()
-        .with_size(400, 100)
-        .with_label("Input Window");
-    win.make_resizable(true);
+    // region Do the button callbacks.
+    let outcome: Outcome<String> = Rc::new(RefCell::new(None));
+
+    let outcome_ok = outcome.clone();
+    let input_ok = input_widget.clone();
+    let mut win_ok = win.clone();
+    ok.set_callback(move |_| {
+        let text = input_ok.value();
+        let result = if text.trim().is_empty() { Err(InputError::Empty) } else { Ok(text) };
+        *outcome_ok.borrow_mut() = Some(result);
+        win_ok.hide();
+    });
 
-    let flex = group::Flex::default()
-        .with_size(200, 75)
-        .column()
-        .center_of_parent();
+    let outcome_cancel = outcome.clone();
+    let mut win_cancel = win.clone();
+    cancel.set_callback(move |_| {
+        *outcome_cancel.borrow_mut() = Some(Err(InputError::Cancelled));
+        win_cancel.hide();
+    });
 
-    let _prompttext = frame::Frame::default().with_label(prompt);
+    let outcome_win = outcome.clone();
+    win.set_callback(move |w| {
+        *outcome_win.borrow_mut() = Some(Err(InputError::Cancelled));
+        w.hide();
+    });
     // endregion
 
-    // region Set up the input widget inside the frame.
-    let mut input_widget = input::FloatInput::default();
-    input_widget.set_trigger(CallbackTrigger::EnterKey);
+    while win.shown() {
+        if !app.wait() {
+            break;
+        }
+    }
+    win.hide();
 
-    // Set the input widget's callback.
-    let mut win2 = win.clone();
-    input_widget.set_callback(move |_| {
-        win2.hide();
-    });
+    let result = outcome.borrow_mut().take();
+    result.unwrap_or(Err(InputError::WindowClosed))
+}
 
-    flex.end();
-    win.end();
-    win.show();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // endregion
+    #[test]
+    fn rules_let_empty_text_through_apart_from_non_empty() {
+        assert!(Rule::Min(1.0).check("  ").is_ok());
+        assert!(Rule::OneOf(vec!["a".to_string()]).check("").is_ok());
+        assert_eq!(Rule::NonEmpty.check(" \t"), Err("A value is required.".to_string()));
+        assert!(Rule::NonEmpty.check("x").is_ok());
+    }
 
-    // region Deal with the input
-    while win.shown() {
-        app.wait();
+    #[test]
+    fn min_and_max_rules_read_numbers() {
+        assert!(Rule::Min(1.5).check(" 1.5 ").is_ok());
+        assert_eq!(Rule::Min(1.5).check("1"), Err("The value can't be less than 1.5.".to_string()));
+        assert!(Rule::Max(10.0).check("10").is_ok());
+        assert_eq!(Rule::Max(10.0).check("10.1"), Err("The value can't be more than 10.".to_string()));
+        assert_eq!(Rule::Min(0.0).check("ten"), Err("Please enter a number.".to_string()));
     }
-    input_widget.value().trim().parse::<f64>().unwrap()
 
-    // endregion
-}
+    #[test]
+    fn text_rules_check_length_pattern_choices_and_closures() {
+        assert!(Rule::MaxLen(3).check("héé").is_ok());
+        assert!(Rule::MaxLen(3).check("abcd").is_err());
+        let zip = Rule::matches(r"^\d{5}$").unwrap();
+        assert!(zip.check("12345").is_ok());
+        assert!(zip.check("1234").is_err());
+        assert!(Rule::matches("(").is_err());
+        let sizes = Rule::OneOf(vec!["S".to_string(), "M".to_string()]);
+        assert!(sizes.check(" M ").is_ok());
+        assert_eq!(sizes.check("L"), Err("The value must be one of:  S, M".to_string()));
+        let one_word = Rule::custom(|text| if text.contains(' ') { Err("One word only.".to_string()) } else { Ok(()) });
+        assert!(one_word.check("abc").is_ok());
+        assert_eq!(one_word.check("a b"), Err("One word only.".to_string()));
+    }
 
+    #[test]
+    fn check_rules_stops_at_the_first_failure() {
+        let rules = [Rule::NonEmpty, Rule::Min(0.0), Rule::Max(5.0)];
+        assert!(check_rules(&rules, "3").is_ok());
+        assert_eq!(check_rules(&rules, ""), Err("A value is required.".to_string()));
+        assert_eq!(check_rules(&rules, "-1"), Err("The value can't be less than 0.".to_string()));
+        assert!(check_rules(&[], "anything").is_ok());
+    }
 
-    
-    
-    
-/*
-    pub fn input_str_large(app: &App, prompt: &str, horiz: i32, vert: i32) -> String {
-        // Works best if you set the horiz and vert to values 10 pixels less than the size
-        //      of the main window with the flex size set to 10 pixels less than that.
+    #[test]
+    fn split_list_text_splits_on_lines_commas_and_tabs() {
+        assert_eq!(split_list_text(" a ,b\tc\r\nd\n\n , "), vec!["a", "b", "c", "d"]);
+        assert!(split_list_text("").is_empty());
+    }
 
-        let mut win = window::Window::default()
-            .with_size(horiz, vert)
-            .with_label("Input Window");
-        win.make_resizable(true);
+    #[test]
+    fn split_list_text_honors_quotes() {
+        assert_eq!(split_list_text(r#""Smith, Jo", "say ""hi""",plain"#), vec!["Smith, Jo", r#"say "hi""#, "plain"]);
+        assert_eq!(split_list_text("\"two\nlines\"\tnext"), vec!["two\nlines", "next"]);
+        assert_eq!(split_list_text(r#"  " padded "  ,x"#), vec![" padded ", "x"]);
+        assert_eq!(split_list_text(r#"5" pipe, "a"b"#), vec![r#"5" pipe"#, "ab"]);
+        assert_eq!(split_list_text(r#""", "open, end"#), vec!["open, end"]);
+    }
+
+    #[test]
+    fn number_keys_let_editing_keys_through() {
+        let us = NumberFormat::us();
+        for key in ["\u{8}", "\u{7f}", "\t", "\r", "\n", "\u{1b}", ""] {
+            assert!(number_text_allowed(key, us, false, false), "{:?}", key);
+        }
+        assert!(number_text_allowed("7", us, true, false));
+        assert!(number_text_allowed(".", us, false, false));
+        assert!(!number_text_allowed(".", us, true, false));
+        assert!(!number_text_allowed("a", us, false, false));
+        assert!(!number_text_allowed(" ", us, false, false));
+    }
+
+    #[test]
+    fn number_paste_takes_spaces_but_not_control_chars() {
+        let us = NumberFormat::us();
+        assert!(number_text_allowed(" 1,234.5 ", us, false, true));
+        assert!(!number_text_allowed("12\u{7}", us, false, true));
+        assert!(!number_text_allowed("1e5", us, false, true));
+    }
 
-        let flex = group::Flex::default()
-            .with_size(horiz-10, vert-10)
-            .column()
-            .center_of_parent();
+    #[test]
+    fn check_grapheme_takes_one_cluster() {
+        assert!(check_grapheme("", &[]).is_ok());
+        assert!(check_grapheme("x", &[]).is_ok());
+        assert!(check_grapheme("e\u{301}", &[]).is_ok());                       // e + combining accent
+        assert!(check_grapheme("\u{1f44d}\u{1f3fd}", &[]).is_ok());            // Thumbs up + skin tone
+        assert!(check_grapheme("\u{1f1e8}\u{1f1e6}", &[]).is_ok());            // A flag
+        assert_eq!(check_grapheme("ab", &[]), Err("Please enter just one character.".to_string()));
+    }
 
-        let _prompttext = frame::Frame::default().with_label(prompt);
+    #[test]
+    fn check_grapheme_keeps_to_the_allowed_set() {
+        let allowed = vec!["Y".to_string(), "N".to_string()];
+        assert!(check_grapheme("Y", &allowed).is_ok());
+        assert!(check_grapheme("", &allowed).is_ok());
+        assert_eq!(check_grapheme("y", &allowed), Err("Please enter one of:  Y, N".to_string()));
+    }
 
-        let mut input = input::MultilineInput::default();
-        input.set_wrap(true);
-        input.set_trigger(CallbackTrigger::EnterKey);
+    #[test]
+    fn number_format_writes_separators() {
+        let us = NumberFormat::us();
+        assert_eq!(us.format_i64(0), "0");
+        assert_eq!(us.format_i64(999), "999");
+        assert_eq!(us.format_i64(1000), "1,000");
+        assert_eq!(us.format_i64(-1234567), "-1,234,567");
+        assert_eq!(us.format_i64(i64::MIN), "-9,223,372,036,854,775,808");
+        assert_eq!(us.format_f64(1234.5, None), "1,234.5");
+        assert_eq!(us.format_f64(1234567.891, Some(2)), "1,234,567.89");
+        assert_eq!(us.format_f64(-0.001, Some(2)), "0.00");
+        assert_eq!(NumberFormat::european().format_f64(1234.5, Some(2)), "1.234,50");
+        assert_eq!(NumberFormat::french().format_i64(1234567), "1 234 567");
+        assert_eq!(NumberFormat::swiss().format_i64(-1234), "-1'234");
+    }
 
-        let mut win2 = win.clone();
-        input.set_callback(move |_input| {
-            win2.hide();
-        });
+    #[test]
+    fn number_format_reads_separators_in_threes() {
+        let us = NumberFormat::us();
+        assert_eq!(us.parse_f64("1,234,567.89"), Some(1234567.89));
+        assert_eq!(us.parse_f64(" 1234567.89 "), Some(1234567.89));
+        assert_eq!(us.parse_f64("-1,000"), Some(-1000.0));
+        assert_eq!(us.parse_f64("+5"), Some(5.0));
+        assert_eq!(us.parse_f64("12,34"), None);
+        assert_eq!(us.parse_f64(",123"), None);
+        assert_eq!(us.parse_f64("1,2345"), None);
+        assert_eq!(us.parse_f64("1e5"), None);
+        assert_eq!(us.parse_i64("1,000"), Some(1000));
+        assert_eq!(us.parse_i64("1,000.5"), None);
+        let fr = NumberFormat::french();
+        assert_eq!(fr.parse_f64("1\u{a0}234,5"), Some(1234.5));
+        assert_eq!(fr.parse_i64("1 234 567"), Some(1234567));
+        assert_eq!(NumberFormat::european().parse_f64("1.234,5"), Some(1234.5));
+    }
 
-        flex.end();
-        win.end();
-        win.show();
+    #[test]
+    fn reformat_number_text_keeps_or_sets_the_decimals() {
+        let us = NumberFormat::us();
+        assert_eq!(reformat_number_text("1234567", us, true, None), Some("1,234,567".to_string()));
+        assert_eq!(reformat_number_text("1234.50", us, false, None), Some("1,234.50".to_string()));
+        assert_eq!(reformat_number_text("1234.5", us, false, Some(2)), Some("1,234.50".to_string()));
+        assert_eq!(reformat_number_text("1,234.5", us, true, None), None);
+        assert_eq!(reformat_number_text("   ", us, false, None), None);
+        assert_eq!(reformat_number_text("12x", us, false, None), None);
+        let de = NumberFormat::european();
+        assert_eq!(reformat_number_text("1234,5", de, false, None), Some("1.234,5".to_string()));
+    }
 
-        while win.shown() {
-            app.wait();
-        }
+    #[test]
+    fn check_range_reads_both_fields_in_order() {
+        let ropts = RangeOptions::<i64>::default();
+        assert_eq!(check_range("3", " 7 ", &ropts), Ok((3, 7)));
+        assert_eq!(check_range("7", "7", &ropts), Ok((7, 7)));
+        assert_eq!(check_range("8", "7", &ropts), Err("The minimum can't be more than the maximum.".to_string()));
+        assert!(check_range("", "7", &ropts).unwrap_err().starts_with("Minimum:"));
+        assert!(check_range("1", "x", &ropts).unwrap_err().starts_with("Maximum:"));
+    }
 
-        input.value()
+    #[test]
+    fn check_range_follows_bounds_steps_and_decimals() {
+        let ropts = RangeOptions::default().with_bounds(0.0, 10.0).with_step(0.1).with_decimals(1);
+        assert_eq!(check_range::<f64>("0.3", "9.9", &ropts), Ok((0.3, 9.9)));
+        assert_eq!(check_range::<f64>("0.35", "1", &ropts),
+                   Err("Minimum:  use no more than 1 decimal places.".to_string()));
+        assert_eq!(check_range::<f64>("-1", "1", &ropts), Err("Minimum:  must be between 0 and 10.".to_string()));
+        let steps = RangeOptions::default().with_bounds(1, 100).with_step(5);
+        assert_eq!(check_range::<i32>("6", "96", &steps), Ok((6, 96)));
+        assert_eq!(check_range::<i32>("5", "96", &steps), Err("Minimum:  must be in steps of 5.".to_string()));
     }
 
- */  // input_str_large()
+    #[test]
+    fn range_format_rounds_to_the_step() {
+        assert_eq!(RangeOptions::<f64>::default().with_step(0.1).format(0.1 + 0.2), "0.3");
+        assert_eq!(RangeOptions::<f64>::default().with_step(0.25).format(2.0), "2");
+        assert_eq!(RangeOptions::<f32>::default().with_step(0.1).format(0.1 + 0.2), "0.3");
+        assert_eq!(RangeOptions::<f64>::default().with_step(0.1).with_decimals(2).format(0.5), "0.50");
+        assert_eq!(RangeOptions::<f64>::default().format(0.25), "0.25");
+        assert_eq!(RangeOptions::<i64>::default().with_step(5).format(15), "15");
+    }
+
+    #[test]
+    fn dual_slider_snaps_to_bounds_and_step() {
+        let st = DualSliderState { lowest: 0.0, highest: 1.0, step: 0.1, low: 0.0, high: 1.0, dragging: None };
+        assert_eq!(st.snap(-3.0), 0.0);
+        assert_eq!(st.snap(7.0), 1.0);
+        assert!((st.snap(0.34) - 0.3).abs() < 1e-12);
+        let free = DualSliderState { step: 0.0, ..st };
+        assert_eq!(free.snap(0.34), 0.34);
+    }
+}
 }
 
 /// User copyable and modifiable templates using the FLTK-RS GUI.